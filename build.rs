@@ -1,10 +1,97 @@
 use std::env;
+use std::fs;
 use std::path::PathBuf;
 
+// the usb identity the firmware enumerates with, each overridable by the
+// environment variable of the same name (see `.cargo/config.toml`).
+const USB_VID: (&str, &str) = ("MODERN_IIE_USB_VID", "0x1209");
+const USB_PID: (&str, &str) = ("MODERN_IIE_USB_PID", "0x0001");
+const USB_MANUFACTURER: (&str, &str) = ("MODERN_IIE_USB_MANUFACTURER", "modernIIe");
+const USB_PRODUCT: (&str, &str) = ("MODERN_IIE_USB_PRODUCT", "modernIIe Keyboard");
+const USB_COUNTRY: (&str, &str) = ("MODERN_IIE_USB_COUNTRY", "US");
+
+// the variants of `usbd_hid::hid_class::HidCountryCode`.
+const COUNTRY_CODES: [&str; 36] = [
+    "NotSupported",
+    "Arabic",
+    "Belgian",
+    "CanadianBilingual",
+    "CanadianFrench",
+    "CzechRepublic",
+    "Danish",
+    "Finnish",
+    "French",
+    "German",
+    "Greek",
+    "Hebrew",
+    "Hungary",
+    "InternationalISO",
+    "Italian",
+    "JapanKatakana",
+    "Korean",
+    "LatinAmerica",
+    "NetherlandsDutch",
+    "Norwegian",
+    "PersianFarsi",
+    "Poland",
+    "Portuguese",
+    "Russia",
+    "Slovakia",
+    "Spanish",
+    "Swedish",
+    "SwissFrench",
+    "SwissGerman",
+    "Switzerland",
+    "Taiwan",
+    "TurkishQ",
+    "UK",
+    "US",
+    "Yugoslavia",
+    "TurkishF",
+];
+
 fn main() {
     // Put `memory.x` in our output directory and ensure it's
     // on the linker search path.
     let out = &PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
     println!("cargo:rustc-link-search={}", out.display());
     println!("cargo:rustc-link-search={}", out.display());
+
+    let vid = usb_id(USB_VID);
+    let pid = usb_id(USB_PID);
+    let manufacturer = setting(USB_MANUFACTURER);
+    let product = setting(USB_PRODUCT);
+    let country = setting(USB_COUNTRY);
+    if !COUNTRY_CODES.contains(&country.as_str()) {
+        fail(
+            USB_COUNTRY.0,
+            format!("{:?} is not a `HidCountryCode`", country),
+        );
+    }
+
+    let generated = format!(
+        "pub const USB_VID: u16 = {:#06x};\n\
+         pub const USB_PID: u16 = {:#06x};\n\
+         pub const USB_MANUFACTURER: &str = {:?};\n\
+         pub const USB_PRODUCT: &str = {:?};\n\
+         pub const USB_COUNTRY_CODE: HidCountryCode = HidCountryCode::{};\n",
+        vid, pid, manufacturer, product, country
+    );
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("usb_identity.rs"), generated).unwrap();
+}
+
+fn setting((name, default): (&str, &str)) -> String {
+    println!("cargo:rerun-if-env-changed={}", name);
+    env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+fn usb_id(var: (&str, &str)) -> u16 {
+    let id = setting(var);
+    u16::from_str_radix(id.trim_start_matches("0x"), 16)
+        .unwrap_or_else(|_| fail(var.0, format!("{:?} is not a hex usb id", id)))
+}
+
+fn fail(name: &str, reason: String) -> ! {
+    panic!("\n\n{}: {}\n\n", name, reason)
 }
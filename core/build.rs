@@ -0,0 +1,157 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+// the keymap definition, see the header of the file for its format.
+const KEYMAP: &str = "keymap.toml";
+
+// every bit a modifier chord may hold, see `kb::input::Modifiers`.
+const MODIFIER_BITS: u8 = 0x01 | 0x02 | 0x04 | 0x40 | 0x80;
+// scan codes index a 128 slot layer, see `kbmap::LAYOUT_KEYS`.
+const LAYOUT_KEYS: u8 = 0x80;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyMapFile {
+    layer: Vec<Layer>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Layer {
+    mask: u8,
+    keys: Vec<Key>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Key {
+    scan_code: u8,
+    key_up: Option<u8>,
+    key_down: Option<u8>,
+    hid: Vec<String>,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", KEYMAP);
+
+    let manifest = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let source = fs::read_to_string(manifest.join(KEYMAP))
+        .unwrap_or_else(|err| fail(format!("unable to read: {}", err)));
+    let keymap: KeyMapFile =
+        toml::from_str(&source).unwrap_or_else(|err| fail(format!("malformed: {}", err)));
+
+    let generated = generate(&keymap).unwrap_or_else(|reason| fail(reason));
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out.join("keymap.rs"), generated).unwrap();
+}
+
+fn fail(reason: String) -> ! {
+    panic!("\n\n{}: {}\n\n", KEYMAP, reason)
+}
+
+/// render the `HID_KEYBOARD_LAYERS` const that `kbmap::hid` includes.
+fn generate(keymap: &KeyMapFile) -> Result<String, String> {
+    let mut masks = BTreeSet::new();
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "// @generated by core/build.rs from {} - edit that instead.\n",
+        KEYMAP
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub const HID_KEYBOARD_LAYERS: [KeyMapLayer; {}] = [",
+        keymap.layer.len()
+    )
+    .unwrap();
+
+    for layer in keymap.layer.iter() {
+        if layer.mask & !MODIFIER_BITS != 0 {
+            return Err(format!(
+                "layer {:#04x} is not a modifier chord, only {:#04x} may be set",
+                layer.mask, MODIFIER_BITS
+            ));
+        }
+        if !masks.insert(layer.mask) {
+            return Err(format!("layer {:#04x} is defined twice", layer.mask));
+        }
+
+        writeln!(out, "    KeyMapLayer::new(").unwrap();
+        writeln!(out, "        LayerMask({:#04x}),", layer.mask).unwrap();
+        writeln!(out, "        &[").unwrap();
+
+        let mut scan_codes = BTreeSet::new();
+        for key in layer.keys.iter() {
+            let at = format!("layer {:#04x} key {:#04x}", layer.mask, key.scan_code);
+
+            if key.scan_code >= LAYOUT_KEYS {
+                return Err(format!(
+                    "{}: scan codes must be below {:#04x}",
+                    at, LAYOUT_KEYS
+                ));
+            }
+            if !scan_codes.insert(key.scan_code) {
+                return Err(format!("{}: is defined twice", at));
+            }
+            if key.hid.is_empty() {
+                return Err(format!("{}: `hid` must list at least one usage", at));
+            }
+
+            let usb_hid = key
+                .hid
+                .iter()
+                .map(|usage| entrant(usage).map_err(|reason| format!("{}: {}", at, reason)))
+                .collect::<Result<Vec<String>, String>>()?;
+
+            writeln!(
+                out,
+                "            ({:#04x}, KeyMapEntry::new({:#04x}, {:#04x}, &[{}])),",
+                key.scan_code,
+                key.key_up.unwrap_or(key.scan_code),
+                key.key_down.unwrap_or(key.scan_code),
+                usb_hid.join(", ")
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "        ],").unwrap();
+        writeln!(out, "    ),").unwrap();
+    }
+
+    writeln!(out, "];").unwrap();
+
+    Ok(out)
+}
+
+/// `Keyboard::<usage>`, `Consumer::<usage>` or `System::<usage>` into its
+/// `KeyboardMapEntrant`. the usage itself is checked by rustc against
+/// `kb::page`, or `kbmap::System`.
+fn entrant(usage: &str) -> Result<String, String> {
+    let (page, name) = usage
+        .split_once("::")
+        .ok_or_else(|| format!("`{}` is not of the form `<page>::<usage>`", usage))?;
+
+    if !matches!(page, "Keyboard" | "Consumer" | "System") {
+        return Err(format!(
+            "`{}` is not a known page, expected `Keyboard`, `Consumer` or `System`",
+            page
+        ));
+    }
+
+    let is_ident = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_ident {
+        return Err(format!("`{}` is not a valid usage name", name));
+    }
+
+    Ok(format!("KeyboardMapEntrant::{}({}::{})", page, page, name))
+}
@@ -0,0 +1,574 @@
+use alloc::vec::Vec;
+
+use super::protocol::*;
+use super::via;
+use crate::health::{Health, ResetReason};
+use crate::kb::input::Modifiers;
+use crate::kb::kbmap::storage::{self, StoredKey, StoredLayer};
+use crate::kb::kbmap::{KeyMap, LAYOUT_KEYS};
+use crate::settings::{Setting, SettingsStore};
+use crate::shared::kb::LayerMask;
+
+/// how long via has to stop editing before its edits are committed.
+pub const VIA_SAVE_DELAY_MS: u32 = 2_000;
+
+/// the most usages a single `GET_KEY`/`SET_KEY` report has room for.
+pub const CONFIG_MAX_USAGES: usize = (CONFIG_REPORT_LEN - 1 - 5) / CONFIG_USAGE_LEN;
+
+/// where `COMMIT` persists a serialized keymap, see `kbmap::storage`.
+pub trait KeyMapStore {
+    /// whether the blob was written.
+    fn store(&mut self, blob: &[u8]) -> bool;
+}
+
+/// the device side of the configuration channel: answers requests against a
+/// draft of the keymap the driver booted with.
+pub struct ConfigChannel {
+    pub(super) version: [u8; 3],
+    pub(super) draft: Vec<StoredLayer>,
+    /// the chords via addresses as layers, fixed at boot so that keys mapped
+    /// on new chords don't shift the layer indexes under the host.
+    pub(super) via_layers: Vec<LayerMask>,
+    /// the uptime of via's latest edit not yet committed.
+    via_edit: Option<u32>,
+    reboot: bool,
+    /// the previous boot's crash log, see `crash`.
+    crash_log: Option<Vec<u8>>,
+    /// kept current by the firmware.
+    pub health: Health,
+}
+
+impl ConfigChannel {
+    pub fn new(version: [u8; 3], key_map: &KeyMap) -> Self {
+        let draft = storage::layers_of(key_map.table);
+        let mut via_layers = draft.iter().map(|(mask, _)| *mask).collect::<Vec<_>>();
+        via_layers.sort();
+
+        Self {
+            version,
+            draft,
+            via_layers,
+            via_edit: None,
+            reboot: false,
+            crash_log: None,
+            health: Health::new(ResetReason::PowerOn),
+        }
+    }
+
+    /// hand the host the log of a crash from before this boot.
+    pub fn set_crash_log(&mut self, crash_log: Vec<u8>) {
+        self.crash_log = Some(crash_log);
+    }
+
+    /// whether the host asked to reboot into the stored keymap, the firmware
+    /// resets once the response has been sent.
+    pub fn reboot_requested(&self) -> bool {
+        self.reboot
+    }
+
+    pub(super) fn request_reboot(&mut self) {
+        self.reboot = true;
+    }
+
+    pub(super) fn via_edited(&mut self) {
+        self.via_edit = Some(self.health.uptime_ms);
+    }
+
+    /// commit via's edits once it has been quiet for `VIA_SAVE_DELAY_MS`, or
+    /// right away ahead of a reboot. called as often as requests are polled.
+    pub fn save_via_edits(&mut self, store: &mut dyn KeyMapStore) {
+        let edited = match self.via_edit {
+            Some(edited) => edited,
+            None => return,
+        };
+        if !self.reboot && self.health.uptime_ms.wrapping_sub(edited) < VIA_SAVE_DELAY_MS {
+            return;
+        }
+        self.via_edit = None;
+        if self.commit(store).is_err() {
+            error!("unable to commit the via keymap");
+        }
+    }
+
+    pub fn handle(
+        &mut self,
+        request: &[u8],
+        store: &mut dyn KeyMapStore,
+        settings: &mut dyn SettingsStore,
+    ) -> [u8; CONFIG_REPORT_LEN] {
+        let mut response = [0u8; CONFIG_REPORT_LEN];
+        let command = request.first().copied().unwrap_or(0x00);
+        if !(GET_VERSION..=GET_HEALTH).contains(&command) {
+            return via::handle(self, request);
+        }
+        let args = request.get(1..).unwrap_or(&[]);
+
+        let (header, data) = response.split_at_mut(CONFIG_RESPONSE_HEADER_LEN);
+        let status = match command {
+            GET_VERSION => self.get_version(data),
+            GET_LAYER_COUNT => self.get_layer_count(data),
+            GET_LAYER => self.get_layer(args, data),
+            GET_KEY => self.get_key(args, data),
+            SET_KEY => self.set_key(args),
+            CLEAR_KEY => self.clear_key(args),
+            COMMIT => self.commit(store),
+            REBOOT => {
+                self.request_reboot();
+                Ok(())
+            }
+            GET_SETTING => get_setting(settings, args, data),
+            SET_SETTING => set_setting(settings, args),
+            GET_CRASH_LOG => self.get_crash_log(args, data),
+            GET_HEALTH => self.get_health(data),
+            _ => Err(Status::UnknownCommand),
+        };
+
+        if let Err(status) = status {
+            error!("config command {} failed: {}", command, status);
+        }
+
+        header[0] = command;
+        header[1] = status.err().unwrap_or(Status::Ok) as u8;
+        response
+    }
+
+    fn get_version(&self, data: &mut [u8]) -> Result<(), Status> {
+        data[..3].copy_from_slice(&self.version);
+        data[3] = CONFIG_PROTOCOL_VERSION;
+        Ok(())
+    }
+
+    fn get_layer_count(&self, data: &mut [u8]) -> Result<(), Status> {
+        data[0] = self.draft.len() as u8;
+        Ok(())
+    }
+
+    fn get_layer(&self, args: &[u8], data: &mut [u8]) -> Result<(), Status> {
+        let (index, offset) = match args {
+            [index, offset, ..] => (*index as usize, *offset as usize),
+            _ => return Err(Status::InvalidArgument),
+        };
+        let (mask, keys) = self.draft.get(index).ok_or(Status::NotFound)?;
+
+        data[0] = mask.0;
+        data[1] = keys.len() as u8;
+        keys.iter()
+            .skip(offset)
+            .zip(data[2..].iter_mut())
+            .for_each(|(key, byte)| *byte = key.0);
+        Ok(())
+    }
+
+    fn get_key(&self, args: &[u8], data: &mut [u8]) -> Result<(), Status> {
+        let (mask, scan_code) = key_address(args)?;
+        let (_scan_code, key_up, key_down, usb_hid) =
+            self.key(mask, scan_code).ok_or(Status::NotFound)?;
+
+        let usages = usb_hid.len().min(CONFIG_MAX_USAGES);
+        data[0] = *key_up;
+        data[1] = *key_down;
+        data[2] = usages as u8;
+        for (entrant, usage) in usb_hid
+            .iter()
+            .take(usages)
+            .zip(data[3..].chunks_exact_mut(CONFIG_USAGE_LEN))
+        {
+            let (page, id) = storage::usage_of(entrant);
+            usage[0] = page;
+            usage[1..].copy_from_slice(&id.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    fn set_key(&mut self, args: &[u8]) -> Result<(), Status> {
+        let (mask, scan_code) = key_address(args)?;
+        let (key_up, key_down, usages) = match args {
+            [_, _, key_up, key_down, usages, ..] => (*key_up, *key_down, *usages as usize),
+            _ => return Err(Status::InvalidArgument),
+        };
+        if usages == 0 || usages > CONFIG_MAX_USAGES {
+            return Err(Status::InvalidArgument);
+        }
+
+        let usb_hid = args[5..]
+            .chunks_exact(CONFIG_USAGE_LEN)
+            .take(usages)
+            .map(|usage| storage::entrant_of(usage[0], u16::from_le_bytes([usage[1], usage[2]])))
+            .collect::<Option<Vec<_>>>()
+            .filter(|usb_hid| usb_hid.len() == usages && storage::fits_reports(usb_hid))
+            .ok_or(Status::InvalidArgument)?;
+
+        self.put_key(mask, (scan_code, key_up, key_down, usb_hid));
+        Ok(())
+    }
+
+    fn clear_key(&mut self, args: &[u8]) -> Result<(), Status> {
+        let (mask, scan_code) = key_address(args)?;
+        self.take_key(mask, scan_code)
+            .map(|_| ())
+            .ok_or(Status::NotFound)
+    }
+
+    fn get_crash_log(&self, args: &[u8], data: &mut [u8]) -> Result<(), Status> {
+        let offset = match args {
+            [low, high, ..] => u16::from_le_bytes([*low, *high]) as usize,
+            _ => return Err(Status::InvalidArgument),
+        };
+        let crash_log = self.crash_log.as_ref().ok_or(Status::NotFound)?;
+
+        data[..2].copy_from_slice(&(crash_log.len() as u16).to_le_bytes());
+        crash_log
+            .iter()
+            .skip(offset)
+            .zip(data[2..].iter_mut())
+            .for_each(|(from, to)| *to = *from);
+        Ok(())
+    }
+
+    fn get_health(&self, data: &mut [u8]) -> Result<(), Status> {
+        data[0] = self.health.reset_reason as u8;
+        data[1..5].copy_from_slice(&self.health.uptime_ms.to_le_bytes());
+        data[5..9].copy_from_slice(&self.health.scan_overruns.to_le_bytes());
+        Ok(())
+    }
+
+    /// the draft's key at `scan_code` of the `mask` layer.
+    pub(super) fn key(&self, mask: LayerMask, scan_code: u8) -> Option<&StoredKey> {
+        self.draft
+            .iter()
+            .find(|(layer, _)| *layer == mask)
+            .and_then(|(_, keys)| keys.iter().find(|key| key.0 == scan_code))
+    }
+
+    /// map a key in the draft, adding its layer if the chord had none.
+    pub(super) fn put_key(&mut self, mask: LayerMask, key: StoredKey) {
+        let keys = match self.draft.iter().position(|(layer, _)| *layer == mask) {
+            Some(index) => &mut self.draft[index].1,
+            None => {
+                self.draft.push((mask, Vec::new()));
+                &mut self.draft.last_mut().unwrap().1
+            }
+        };
+        match keys.binary_search_by_key(&key.0, |key| key.0) {
+            Ok(index) => keys[index] = key,
+            Err(index) => keys.insert(index, key),
+        }
+    }
+
+    /// unmap a key from the draft.
+    pub(super) fn take_key(&mut self, mask: LayerMask, scan_code: u8) -> Option<StoredKey> {
+        let keys = self
+            .draft
+            .iter_mut()
+            .find(|(layer, _)| *layer == mask)
+            .map(|(_, keys)| keys)?;
+        let index = keys.iter().position(|key| key.0 == scan_code)?;
+        Some(keys.remove(index))
+    }
+
+    pub(super) fn commit(&mut self, store: &mut dyn KeyMapStore) -> Result<(), Status> {
+        let blob = storage::encode_layers(&self.draft);
+        if store.store(&blob) {
+            // the draft holds via's edits too, they're saved along with it.
+            self.via_edit = None;
+            info!("committed a {} byte keymap", blob.len());
+            Ok(())
+        } else {
+            Err(Status::StorageFailed)
+        }
+    }
+}
+
+fn get_setting(settings: &dyn SettingsStore, args: &[u8], data: &mut [u8]) -> Result<(), Status> {
+    let setting = args
+        .first()
+        .and_then(|id| Setting::get(*id))
+        .ok_or(Status::NotFound)?;
+    data[..2].copy_from_slice(&settings.get(setting).to_le_bytes());
+    Ok(())
+}
+
+fn set_setting(settings: &mut dyn SettingsStore, args: &[u8]) -> Result<(), Status> {
+    let (setting, value) = match args {
+        [id, low, high, ..] => (
+            Setting::get(*id).ok_or(Status::NotFound)?,
+            u16::from_le_bytes([*low, *high]),
+        ),
+        _ => return Err(Status::InvalidArgument),
+    };
+    if !setting.accepts(value) {
+        return Err(Status::InvalidArgument);
+    }
+    if !settings.set(setting, value) {
+        return Err(Status::StorageFailed);
+    }
+    info!("setting {} is now {}", setting as u8, value);
+    Ok(())
+}
+
+/// the `mask, scan code` a key command starts with.
+fn key_address(args: &[u8]) -> Result<(LayerMask, u8), Status> {
+    match args {
+        [mask, scan_code, ..] if (*scan_code as usize) < LAYOUT_KEYS => {
+            let mask = Modifiers::get(*mask).ok_or(Status::InvalidArgument)?;
+            Ok((LayerMask::from(mask), *scan_code))
+        }
+        _ => Err(Status::InvalidArgument),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kb::kbmap::KeyboardMapEntrant;
+    use crate::kb::page::{Consumer, Keyboard};
+    use alloc::vec;
+
+    /// a keymap store in ram, refusing every blob while `full`.
+    #[derive(Default)]
+    struct MemStore {
+        blob: Option<Vec<u8>>,
+        full: bool,
+    }
+
+    impl KeyMapStore for MemStore {
+        fn store(&mut self, blob: &[u8]) -> bool {
+            if !self.full {
+                self.blob = Some(blob.to_vec());
+            }
+            !self.full
+        }
+    }
+
+    struct MemSettings(Vec<(Setting, u16)>);
+
+    impl SettingsStore for MemSettings {
+        fn get(&self, setting: Setting) -> u16 {
+            self.0
+                .iter()
+                .rev()
+                .find(|(stored, _)| *stored == setting)
+                .map_or(setting.default_value(), |(_, value)| *value)
+        }
+
+        fn set(&mut self, setting: Setting, value: u16) -> bool {
+            self.0.push((setting, value));
+            true
+        }
+    }
+
+    /// the bare layer maps `A` and `a`, the open apple layer mute.
+    fn layers() -> Vec<StoredLayer> {
+        vec![
+            (
+                LayerMask(0x00),
+                vec![
+                    (
+                        0x41,
+                        0x41,
+                        0xC1,
+                        vec![KeyboardMapEntrant::Keyboard(Keyboard::A)],
+                    ),
+                    (
+                        0x61,
+                        0x61,
+                        0xE1,
+                        vec![
+                            KeyboardMapEntrant::Keyboard(Keyboard::LeftShift),
+                            KeyboardMapEntrant::Keyboard(Keyboard::A),
+                        ],
+                    ),
+                ],
+            ),
+            (
+                LayerMask(0x40),
+                vec![(
+                    0x31,
+                    0x31,
+                    0xB1,
+                    vec![KeyboardMapEntrant::Consumer(Consumer::Mute)],
+                )],
+            ),
+        ]
+    }
+
+    fn channel() -> ConfigChannel {
+        let table = storage::decode(&storage::encode_layers(&layers()))
+            .ok()
+            .unwrap();
+        ConfigChannel::new([1, 2, 3], &KeyMap { table })
+    }
+
+    /// send `request` zero padded, as the host does, and split the response
+    /// into its status and data.
+    fn send(
+        channel: &mut ConfigChannel,
+        store: &mut MemStore,
+        request: &[u8],
+    ) -> (
+        Option<Status>,
+        [u8; CONFIG_REPORT_LEN - CONFIG_RESPONSE_HEADER_LEN],
+    ) {
+        let mut report = [0u8; CONFIG_REPORT_LEN];
+        report[..request.len()].copy_from_slice(request);
+
+        let mut settings = MemSettings(Vec::new());
+        let response = channel.handle(&report, store, &mut settings);
+        assert!(response[0] == request[0]);
+
+        let mut data = [0u8; CONFIG_REPORT_LEN - CONFIG_RESPONSE_HEADER_LEN];
+        data.copy_from_slice(&response[CONFIG_RESPONSE_HEADER_LEN..]);
+        (Status::get(response[1]), data)
+    }
+
+    #[test]
+    fn get_version() {
+        let (mut channel, mut store) = (channel(), MemStore::default());
+        let (status, data) = send(&mut channel, &mut store, &[GET_VERSION]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..4] == [1, 2, 3, CONFIG_PROTOCOL_VERSION]);
+    }
+
+    #[test]
+    fn lists_layers() {
+        let (mut channel, mut store) = (channel(), MemStore::default());
+
+        let (status, data) = send(&mut channel, &mut store, &[GET_LAYER_COUNT]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[0] == 2);
+
+        let (status, data) = send(&mut channel, &mut store, &[GET_LAYER, 0, 0]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..5] == [0x00, 2, 0x41, 0x61, 0x00]);
+
+        // from an offset into the layer's keys.
+        let (status, data) = send(&mut channel, &mut store, &[GET_LAYER, 0, 1]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..4] == [0x00, 2, 0x61, 0x00]);
+
+        let (status, data) = send(&mut channel, &mut store, &[GET_LAYER, 1, 0]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..4] == [0x40, 1, 0x31, 0x00]);
+
+        let (status, _) = send(&mut channel, &mut store, &[GET_LAYER, 2, 0]);
+        assert!(status == Some(Status::NotFound));
+    }
+
+    #[test]
+    fn get_and_set_key() {
+        let (mut channel, mut store) = (channel(), MemStore::default());
+
+        let (status, data) = send(&mut channel, &mut store, &[GET_KEY, 0x00, 0x61]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..9] == [0x61, 0xE1, 2, 0x07, 0xE1, 0x00, 0x07, 0x04, 0x00]);
+
+        // a new key on the open apple layer, then remapping it.
+        let set = [SET_KEY, 0x40, 0x32, 0x32, 0xB2, 1, 0x0C, 0xE9, 0x00];
+        let (status, _) = send(&mut channel, &mut store, &set);
+        assert!(status == Some(Status::Ok));
+        let set = [SET_KEY, 0x40, 0x32, 0x32, 0xB2, 1, 0x0C, 0xEA, 0x00];
+        let (status, _) = send(&mut channel, &mut store, &set);
+        assert!(status == Some(Status::Ok));
+
+        let (status, data) = send(&mut channel, &mut store, &[GET_KEY, 0x40, 0x32]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..6] == [0x32, 0xB2, 1, 0x0C, 0xEA, 0x00]);
+
+        let (status, data) = send(&mut channel, &mut store, &[GET_LAYER, 1, 0]);
+        assert!(status == Some(Status::Ok));
+        assert!(data[..4] == [0x40, 2, 0x31, 0x32]);
+
+        // a key on a chord without a layer yet adds one.
+        let set = [SET_KEY, 0x01, 0x41, 0x41, 0xC1, 1, 0x07, 0x04, 0x00];
+        let (status, _) = send(&mut channel, &mut store, &set);
+        assert!(status == Some(Status::Ok));
+        let (_, data) = send(&mut channel, &mut store, &[GET_LAYER_COUNT]);
+        assert!(data[0] == 3);
+
+        let (status, _) = send(&mut channel, &mut store, &[CLEAR_KEY, 0x40, 0x32]);
+        assert!(status == Some(Status::Ok));
+        let (status, _) = send(&mut channel, &mut store, &[GET_KEY, 0x40, 0x32]);
+        assert!(status == Some(Status::NotFound));
+    }
+
+    #[test]
+    fn rejects_out_of_range_keys() {
+        let (mut channel, mut store) = (channel(), MemStore::default());
+
+        // a scan code past the layout, a mask that isn't a chord.
+        for request in [
+            [GET_KEY, 0x00, 0x80],
+            [GET_KEY, 0x08, 0x41],
+            [CLEAR_KEY, 0x00, 0xC1],
+        ] {
+            let (status, _) = send(&mut channel, &mut store, &request);
+            assert!(status == Some(Status::InvalidArgument));
+        }
+
+        // a chord without a layer, a key its layer leaves unmapped.
+        for request in [[GET_KEY, 0x01, 0x41], [GET_KEY, 0x40, 0x41]] {
+            let (status, _) = send(&mut channel, &mut store, &request);
+            assert!(status == Some(Status::NotFound));
+        }
+
+        let too_many = CONFIG_MAX_USAGES as u8 + 1;
+        for set in [
+            [SET_KEY, 0x00, 0x80, 0x00, 0x80, 1, 0x07, 0x04, 0x00],
+            [SET_KEY, 0x08, 0x41, 0x41, 0xC1, 1, 0x07, 0x04, 0x00],
+            // no usages, more than a report has room for, fewer than claimed.
+            [SET_KEY, 0x00, 0x41, 0x41, 0xC1, 0, 0x07, 0x04, 0x00],
+            [SET_KEY, 0x00, 0x41, 0x41, 0xC1, too_many, 0x07, 0x04, 0x00],
+            [SET_KEY, 0x00, 0x41, 0x41, 0xC1, 2, 0x07, 0x04, 0x00],
+            // an unknown usage page.
+            [SET_KEY, 0x00, 0x41, 0x41, 0xC1, 1, 0x09, 0x01, 0x00],
+        ] {
+            let (status, _) = send(&mut channel, &mut store, &set);
+            assert!(status == Some(Status::InvalidArgument));
+        }
+
+        // two consumer usages don't fit the consumer report.
+        let set = [
+            SET_KEY, 0x00, 0x41, 0x41, 0xC1, 2, 0x0C, 0xE2, 0x00, 0x0C, 0xE9, 0x00,
+        ];
+        let (status, _) = send(&mut channel, &mut store, &set);
+        assert!(status == Some(Status::InvalidArgument));
+
+        // the draft is as it was.
+        assert!(channel.draft == layers());
+    }
+
+    #[test]
+    fn commit_stores_the_draft() {
+        let (mut channel, mut store) = (channel(), MemStore::default());
+        let set = [SET_KEY, 0x40, 0x32, 0x32, 0xB2, 1, 0x0C, 0xE9, 0x00];
+        send(&mut channel, &mut store, &set);
+
+        let (status, _) = send(&mut channel, &mut store, &[COMMIT]);
+        assert!(status == Some(Status::Ok));
+        let stored = storage::decode_layers(store.blob.as_ref().unwrap());
+        assert!(stored == Ok(channel.draft.clone()));
+
+        store.full = true;
+        store.blob = None;
+        let (status, _) = send(&mut channel, &mut store, &[COMMIT]);
+        assert!(status == Some(Status::StorageFailed));
+        assert!(store.blob.is_none());
+    }
+
+    #[test]
+    fn unknown_commands_go_to_via() {
+        let (mut channel, mut store) = (channel(), MemStore::default());
+        let mut settings = MemSettings(Vec::new());
+
+        // via's protocol version, big endian right after the command.
+        let mut request = [0u8; CONFIG_REPORT_LEN];
+        request[0] = 0x01;
+        let response = channel.handle(&request, &mut store, &mut settings);
+        assert!(response[..3] == [0x01, 0x00, 0x0C]);
+
+        // neither protocol's, via marks it unhandled.
+        request[0] = 0x50;
+        let response = channel.handle(&request, &mut store, &mut settings);
+        assert!(response[0] == 0xFF);
+    }
+}
@@ -0,0 +1,8 @@
+//! the vendor defined raw hid configuration channel, over which a host can
+//! inspect and remap the keymap without reflashing firmware.
+
+mod channel;
+pub mod protocol;
+pub mod via;
+
+pub use channel::*;
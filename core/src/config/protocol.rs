@@ -0,0 +1,82 @@
+//! the wire format of the configuration channel, shared by the firmware and
+//! the host tools.
+//!
+//! every request and response is a single `CONFIG_REPORT_LEN` byte report.
+//! a request is `command, arguments..` and its response echoes the command
+//! followed by a `Status` and the response data, zero padded.
+//!
+//! | command           | arguments                                     | response data                                   |
+//! |-------------------|-----------------------------------------------|-------------------------------------------------|
+//! | `GET_VERSION`     |                                               | major, minor, patch, `CONFIG_PROTOCOL_VERSION`  |
+//! | `GET_LAYER_COUNT` |                                               | layer count                                     |
+//! | `GET_LAYER`       | layer index, key offset                       | mask, key count, scan codes from the offset..   |
+//! | `GET_KEY`         | mask, scan code                               | key up, key down, usage count, usages..         |
+//! | `SET_KEY`         | mask, scan code, key up, key down, usage count, usages.. |                                      |
+//! | `CLEAR_KEY`       | mask, scan code                               |                                                 |
+//! | `COMMIT`          |                                               |                                                 |
+//! | `REBOOT`          |                                               |                                                 |
+//! | `GET_SETTING`     | `Setting`                                     | value (u16 le)                                  |
+//! | `SET_SETTING`     | `Setting`, value (u16 le)                     |                                                 |
+//! | `GET_CRASH_LOG`   | offset (u16 le)                               | log length (u16 le), the log from the offset..  |
+//! | `GET_HEALTH`      |                                               | `ResetReason`, uptime ms, scan overruns (u32 le) |
+//!
+//! any other command is answered as via would, see `config::via`.
+//!
+//! a usage is `usage page, usage id (u16 le)`, see `kbmap::storage`. edits
+//! are made to a draft of the keymap which `COMMIT` persists to flash, the
+//! stored keymap is then loaded on the next boot (`REBOOT`). settings are
+//! persisted as they're set and likewise take effect on the next boot.
+//! `GET_CRASH_LOG` answers `NotFound` unless the previous boot crashed, see
+//! `crash`.
+
+/// the usb hid usage page and usage of the configuration interface.
+pub const CONFIG_USAGE_PAGE: u16 = 0xFF60;
+pub const CONFIG_USAGE: u8 = 0x61;
+
+pub const CONFIG_REPORT_LEN: usize = 32;
+/// bumped whenever a command changes shape.
+pub const CONFIG_PROTOCOL_VERSION: u8 = 1;
+
+// the response header, command and status.
+pub const CONFIG_RESPONSE_HEADER_LEN: usize = 2;
+/// a usage on the wire, page and u16 id.
+pub const CONFIG_USAGE_LEN: usize = 3;
+
+// kept clear of the command ids of the via protocol sharing the interface,
+// see `config::via`.
+pub const GET_VERSION: u8 = 0xA1;
+pub const GET_LAYER_COUNT: u8 = 0xA2;
+pub const GET_LAYER: u8 = 0xA3;
+pub const GET_KEY: u8 = 0xA4;
+pub const SET_KEY: u8 = 0xA5;
+pub const CLEAR_KEY: u8 = 0xA6;
+pub const COMMIT: u8 = 0xA7;
+pub const REBOOT: u8 = 0xA8;
+pub const GET_SETTING: u8 = 0xA9;
+pub const SET_SETTING: u8 = 0xAA;
+pub const GET_CRASH_LOG: u8 = 0xAB;
+pub const GET_HEALTH: u8 = 0xAC;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Status {
+    Ok = 0x00,
+    UnknownCommand = 0x01,
+    InvalidArgument = 0x02,
+    NotFound = 0x03,
+    StorageFailed = 0x04,
+}
+
+impl Status {
+    pub fn get(status: u8) -> Option<Status> {
+        match status {
+            0x00 => Some(Status::Ok),
+            0x01 => Some(Status::UnknownCommand),
+            0x02 => Some(Status::InvalidArgument),
+            0x03 => Some(Status::NotFound),
+            0x04 => Some(Status::StorageFailed),
+            _ => None,
+        }
+    }
+}
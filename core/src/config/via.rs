@@ -0,0 +1,425 @@
+//! enough of the via raw hid protocol (v12) for via to remap the keymap with
+//! `via/modern_iie.json`. vial's own commands aren't spoken, so vial can't.
+//!
+//! via addresses keys by layer, row and column. its layers are the chords of
+//! `ConfigChannel::via_layers`, bare first. its matrix is the IIe matrix,
+//! `NUM_ROWS` x `NUM_COLS` at scan code `(column * 16) + row`, plus a row of
+//! the `NUM_MODS` modifier lines in `MODIFIER_LINES` order. a modifier on a
+//! layer is the bare entry of that layer's chord with the modifier held.
+//!
+//! keycodes are qmk's. keyboard usages are basic keycodes, the consumer and
+//! system control keys map to the media and system keycodes and modifiers plus
+//! a key are `QK_MODS`. a key
+//! that doesn't fit in one keycode reads as `KC_NO`, a key a layer leaves to
+//! the bare layer reads as `KC_TRNS`.
+//!
+//! via has no save, it writes a keymap a key at a time. its edits stay in the
+//! draft until it has been quiet for `VIA_SAVE_DELAY_MS`, see
+//! `ConfigChannel::save_via_edits`, and like the native protocol take effect
+//! on the next boot.
+
+use alloc::vec::Vec;
+
+use super::channel::ConfigChannel;
+use super::protocol::CONFIG_REPORT_LEN;
+use crate::kb::decoder::{MODIFIER_LINES, NUM_COLS, NUM_MODS, NUM_ROWS};
+use crate::kb::input::Modifiers;
+use crate::kb::kbmap::storage::{self, PAGE_CONSUMER, PAGE_DESKTOP, PAGE_KEYBOARD};
+use crate::kb::kbmap::{KeyMap, KeyboardMapEntrant};
+use crate::shared::kb::LayerMask;
+
+pub const VIA_PROTOCOL_VERSION: u16 = 0x000C;
+/// the IIe matrix plus the modifier row.
+pub const VIA_ROWS: usize = NUM_ROWS + 1;
+pub const VIA_COLS: usize = NUM_COLS;
+
+const ID_GET_PROTOCOL_VERSION: u8 = 0x01;
+const ID_GET_KEYBOARD_VALUE: u8 = 0x02;
+const ID_SET_KEYBOARD_VALUE: u8 = 0x03;
+const ID_DYNAMIC_KEYMAP_GET_KEYCODE: u8 = 0x04;
+const ID_DYNAMIC_KEYMAP_SET_KEYCODE: u8 = 0x05;
+const ID_DYNAMIC_KEYMAP_RESET: u8 = 0x06;
+const ID_DYNAMIC_KEYMAP_MACRO_GET_COUNT: u8 = 0x0C;
+const ID_DYNAMIC_KEYMAP_MACRO_GET_BUFFER_SIZE: u8 = 0x0D;
+const ID_DYNAMIC_KEYMAP_GET_LAYER_COUNT: u8 = 0x11;
+const ID_DYNAMIC_KEYMAP_GET_BUFFER: u8 = 0x12;
+const ID_DYNAMIC_KEYMAP_SET_BUFFER: u8 = 0x13;
+const ID_UNHANDLED: u8 = 0xFF;
+
+// the values of `ID_GET_KEYBOARD_VALUE`.
+const ID_UPTIME: u8 = 0x01;
+const ID_LAYOUT_OPTIONS: u8 = 0x02;
+const ID_SWITCH_MATRIX_STATE: u8 = 0x03;
+const ID_FIRMWARE_VERSION: u8 = 0x04;
+
+// the keymap buffer starts after `command, offset (u16 be), size`.
+const BUFFER_HEADER_LEN: usize = 4;
+const BUFFER_MAX_LEN: usize = CONFIG_REPORT_LEN - BUFFER_HEADER_LEN;
+
+const KC_NO: u16 = 0x0000;
+const KC_TRNS: u16 = 0x0001;
+const QK_MODS: u16 = 0x0100;
+const QK_MODS_MAX: u16 = 0x1FFF;
+// the modifier bits of `QK_MODS`, in keyboard usage order (0xE0..).
+const MOD_BITS: [u16; 4] = [0x01, 0x02, 0x04, 0x08]; // ctrl, shift, alt, gui
+const MOD_RIGHT: u16 = 0x10;
+
+// the modifier usages of the keyboard page, left then right.
+const KEYBOARD_LEFT_CONTROL: u16 = 0xE0;
+const KEYBOARD_RIGHT_CONTROL: u16 = 0xE4;
+const KEYBOARD_RIGHT_GUI: u16 = 0xE7;
+
+/// `(consumer usage, qmk keycode)` of the media keys.
+const CONSUMER_KEYCODES: [(u16, u16); 10] = [
+    (0x00E2, 0x00A8), // Mute
+    (0x00E9, 0x00A9), // Volume Increment
+    (0x00EA, 0x00AA), // Volume Decrement
+    (0x00B5, 0x00AB), // Scan Next Track
+    (0x00B6, 0x00AC), // Scan Previous Track
+    (0x00B7, 0x00AD), // Stop
+    (0x00CD, 0x00AE), // Play/Pause
+    (0x00B8, 0x00B0), // Eject
+    (0x00B3, 0x00BB), // Fast Forward
+    (0x00B4, 0x00BC), // Rewind
+];
+
+/// `(generic desktop usage, qmk keycode)` of the system control keys.
+const SYSTEM_KEYCODES: [(u16, u16); 3] = [
+    (0x0081, 0x00A5), // System Power Down
+    (0x0082, 0x00A6), // System Sleep
+    (0x0083, 0x00A7), // System Wake Up
+];
+
+/// answer a via request. the response is the request with its data filled in,
+/// or its command replaced by `ID_UNHANDLED`.
+pub fn handle(channel: &mut ConfigChannel, request: &[u8]) -> [u8; CONFIG_REPORT_LEN] {
+    let mut response = [0u8; CONFIG_REPORT_LEN];
+    let len = request.len().min(CONFIG_REPORT_LEN);
+    response[..len].copy_from_slice(&request[..len]);
+
+    match response[0] {
+        ID_GET_PROTOCOL_VERSION => {
+            response[1..3].copy_from_slice(&VIA_PROTOCOL_VERSION.to_be_bytes());
+        }
+        ID_GET_KEYBOARD_VALUE => match response[1] {
+            // none of these are tracked, zeros keep via's key tester idle.
+            ID_UPTIME | ID_SWITCH_MATRIX_STATE | ID_LAYOUT_OPTIONS => {
+                response[2..].fill(0x00);
+            }
+            ID_FIRMWARE_VERSION => {
+                let [major, minor, patch] = channel.version;
+                response[2..6].copy_from_slice(&[0x00, major, minor, patch]);
+            }
+            _ => response[0] = ID_UNHANDLED,
+        },
+        // there is a single layout, any option is as good as another.
+        ID_SET_KEYBOARD_VALUE => {}
+        ID_DYNAMIC_KEYMAP_GET_KEYCODE => {
+            let (layer, row, col) = (response[1], response[2], response[3]);
+            let keycode = get_keycode(channel, layer as usize, row as usize, col as usize);
+            response[4..6].copy_from_slice(&keycode.to_be_bytes());
+        }
+        ID_DYNAMIC_KEYMAP_SET_KEYCODE => {
+            let (layer, row, col) = (response[1], response[2], response[3]);
+            let keycode = u16::from_be_bytes([response[4], response[5]]);
+            if set_keycode(channel, layer as usize, row as usize, col as usize, keycode) {
+                channel.via_edited();
+            }
+        }
+        ID_DYNAMIC_KEYMAP_RESET => {
+            channel.draft = storage::layers_of(KeyMap::init().table);
+            channel.via_edited();
+        }
+        // the oracle already renders macros from the keymap, via's aren't offered.
+        ID_DYNAMIC_KEYMAP_MACRO_GET_COUNT => response[1] = 0,
+        ID_DYNAMIC_KEYMAP_MACRO_GET_BUFFER_SIZE => response[1..3].fill(0x00),
+        ID_DYNAMIC_KEYMAP_GET_LAYER_COUNT => response[1] = channel.via_layers.len() as u8,
+        ID_DYNAMIC_KEYMAP_GET_BUFFER => {
+            let (offset, size) = buffer_window(&response);
+            for i in 0..size {
+                let keycode = keycode_at(channel, (offset + i) / 2);
+                response[BUFFER_HEADER_LEN + i] = keycode.to_be_bytes()[(offset + i) % 2];
+            }
+        }
+        ID_DYNAMIC_KEYMAP_SET_BUFFER => {
+            let (offset, size) = buffer_window(&response);
+            let mut changed = false;
+            for index in offset / 2..(offset + size + 1) / 2 {
+                let mut bytes = keycode_at(channel, index).to_be_bytes();
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    if let Some(at) = (index * 2 + i).checked_sub(offset).filter(|at| *at < size) {
+                        *byte = response[BUFFER_HEADER_LEN + at];
+                    }
+                }
+                let (layer, row, col) = position_of(index);
+                changed |= set_keycode(channel, layer, row, col, u16::from_be_bytes(bytes));
+            }
+            if changed {
+                channel.via_edited();
+            }
+        }
+        _ => response[0] = ID_UNHANDLED,
+    }
+
+    response
+}
+
+/// the `(offset, size)` of a buffer request, clamped to the report.
+fn buffer_window(request: &[u8; CONFIG_REPORT_LEN]) -> (usize, usize) {
+    let offset = u16::from_be_bytes([request[1], request[2]]) as usize;
+    let size = (request[3] as usize).min(BUFFER_MAX_LEN);
+    (offset, size)
+}
+
+/// the `(layer, row, col)` of a keycode in via's keymap buffer.
+fn position_of(index: usize) -> (usize, usize, usize) {
+    let layer_size = VIA_ROWS * VIA_COLS;
+    let key = index % layer_size;
+    (index / layer_size, key / VIA_COLS, key % VIA_COLS)
+}
+
+fn keycode_at(channel: &ConfigChannel, index: usize) -> u16 {
+    let (layer, row, col) = position_of(index);
+    get_keycode(channel, layer, row, col)
+}
+
+/// the `(mask, scan code)` of the key via has at `layer, row, col`.
+fn address(
+    channel: &ConfigChannel,
+    layer: usize,
+    row: usize,
+    col: usize,
+) -> Option<(LayerMask, u8)> {
+    let layer = *channel.via_layers.get(layer)?;
+    if row < NUM_ROWS && col < NUM_COLS {
+        Some((layer, (col * 16 + row) as u8))
+    } else if row == NUM_ROWS && col < NUM_MODS {
+        let chord = Modifiers::from_bits_truncate(layer.0) | MODIFIER_LINES[col];
+        Some((LayerMask::from(chord), 0x00))
+    } else {
+        None
+    }
+}
+
+fn get_keycode(channel: &ConfigChannel, layer: usize, row: usize, col: usize) -> u16 {
+    match address(channel, layer, row, col) {
+        Some((mask, scan_code)) => match channel.key(mask, scan_code) {
+            Some(key) => keycode_of(&key.3),
+            None if layer == 0 => KC_NO,
+            None => KC_TRNS,
+        },
+        None => KC_NO,
+    }
+}
+
+/// whether the draft changed.
+fn set_keycode(
+    channel: &mut ConfigChannel,
+    layer: usize,
+    row: usize,
+    col: usize,
+    keycode: u16,
+) -> bool {
+    let (mask, scan_code) = match address(channel, layer, row, col) {
+        Some(address) => address,
+        None => return false,
+    };
+    if get_keycode(channel, layer, row, col) == keycode {
+        return false;
+    }
+
+    if keycode == KC_NO || keycode == KC_TRNS {
+        return channel.take_key(mask, scan_code).is_some();
+    }
+
+    match usb_hid_of(keycode) {
+        Some(usb_hid) => {
+            let (key_up, key_down) = channel
+                .key(mask, scan_code)
+                .map(|key| (key.1, key.2))
+                .unwrap_or((scan_code, scan_code));
+            channel.put_key(mask, (scan_code, key_up, key_down, usb_hid));
+            true
+        }
+        None => {
+            error!("via keycode {} has no usb hid equivalent", keycode);
+            false
+        }
+    }
+}
+
+/// the qmk keycode a key's usages render as, `KC_NO` if there is none.
+fn keycode_of(usb_hid: &[KeyboardMapEntrant]) -> u16 {
+    let usages = usb_hid
+        .iter()
+        .map(storage::usage_of)
+        .collect::<Vec<(u8, u16)>>();
+
+    let special = match usages.as_slice() {
+        [(PAGE_CONSUMER, usage)] => Some((CONSUMER_KEYCODES.as_slice(), usage)),
+        [(PAGE_DESKTOP, usage)] => Some((SYSTEM_KEYCODES.as_slice(), usage)),
+        _ => None,
+    };
+    if let Some((keycodes, usage)) = special {
+        return keycodes
+            .iter()
+            .find(|(special, _)| special == usage)
+            .map(|(_, keycode)| *keycode)
+            .unwrap_or(KC_NO);
+    }
+    if usages.iter().any(|(page, _)| *page != PAGE_KEYBOARD) {
+        return KC_NO;
+    }
+
+    let is_modifier = |usage: &u16| (KEYBOARD_LEFT_CONTROL..=KEYBOARD_RIGHT_GUI).contains(usage);
+    let (mut modifiers, keys): (Vec<u16>, Vec<u16>) = usages
+        .iter()
+        .map(|(_, usage)| *usage)
+        .partition(is_modifier);
+
+    // a lone chord of modifiers is its last modifier with the rest held, the
+    // order `usb_hid_of` renders a modified modifier in.
+    let key = match keys.as_slice() {
+        [key] => *key,
+        [] if !modifiers.is_empty() => modifiers.pop().unwrap(),
+        _ => return KC_NO,
+    };
+
+    let right = modifiers
+        .iter()
+        .filter(|usage| **usage >= KEYBOARD_RIGHT_CONTROL)
+        .count();
+    if right != 0 && right != modifiers.len() {
+        // qmk can't hold left and right modifiers in a single keycode.
+        return KC_NO;
+    }
+
+    let mods = modifiers.iter().fold(0u16, |acc, usage| {
+        acc | MOD_BITS[((usage - KEYBOARD_LEFT_CONTROL) % 4) as usize]
+    });
+    match mods {
+        0 => key,
+        _ if right != 0 => ((mods | MOD_RIGHT) << 8) | key,
+        _ => (mods << 8) | key,
+    }
+}
+
+/// the usages a qmk keycode renders to, `None` for one via can't express here.
+fn usb_hid_of(keycode: u16) -> Option<Vec<KeyboardMapEntrant>> {
+    if let Some((usage, _)) = CONSUMER_KEYCODES.iter().find(|(_, kc)| *kc == keycode) {
+        return storage::entrant_of(PAGE_CONSUMER, *usage).map(|entrant| alloc::vec![entrant]);
+    }
+    if let Some((usage, _)) = SYSTEM_KEYCODES.iter().find(|(_, kc)| *kc == keycode) {
+        return storage::entrant_of(PAGE_DESKTOP, *usage).map(|entrant| alloc::vec![entrant]);
+    }
+
+    let (mods, key) = match keycode {
+        0x0004..=0x00A4 | 0x00E0..=0x00E7 => (0, keycode),
+        QK_MODS..=QK_MODS_MAX => ((keycode >> 8) & 0x1F, keycode & 0xFF),
+        _ => return None,
+    };
+
+    let offset = if mods & MOD_RIGHT != 0 { 4 } else { 0 };
+    let mut usages = MOD_BITS
+        .iter()
+        .enumerate()
+        .filter(|(_, bit)| mods & **bit != 0)
+        .map(|(i, _)| KEYBOARD_LEFT_CONTROL + offset + i as u16)
+        .collect::<Vec<u16>>();
+    if key != KC_NO {
+        usages.push(key);
+    }
+    if usages.is_empty() || usages.iter().any(|usage| *usage > KEYBOARD_RIGHT_GUI) {
+        return None;
+    }
+
+    usages
+        .into_iter()
+        .map(|usage| storage::entrant_of(PAGE_KEYBOARD, usage))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kb::kbmap::System;
+    use crate::kb::page::{Consumer, Keyboard};
+    use alloc::vec;
+
+    fn round_trips(keycode: u16) -> bool {
+        usb_hid_of(keycode).map(|usb_hid| keycode_of(&usb_hid)) == Some(keycode)
+    }
+
+    #[test]
+    fn basic_keycodes_round_trip() {
+        for keycode in (0x0004..=0x00A4).chain(0x00E0..=0x00E7) {
+            assert!(round_trips(keycode), "{:#06x}", keycode);
+        }
+        assert!(usb_hid_of(0x0004) == Some(vec![KeyboardMapEntrant::Keyboard(Keyboard::A)]));
+    }
+
+    #[test]
+    fn media_and_system_keycodes_round_trip() {
+        for (usage, keycode) in CONSUMER_KEYCODES {
+            assert!(round_trips(keycode), "{:#06x}", keycode);
+            let usb_hid = vec![KeyboardMapEntrant::Consumer(Consumer(usage))];
+            assert!(usb_hid_of(keycode) == Some(usb_hid));
+        }
+        for keycode in 0x00A5..=0x00A7 {
+            assert!(round_trips(keycode), "{:#06x}", keycode);
+        }
+        let sleep = vec![KeyboardMapEntrant::System(System::Sleep)];
+        assert!(usb_hid_of(0x00A6) == Some(sleep));
+    }
+
+    #[test]
+    fn modified_keycodes_round_trip() {
+        // LSFT(KC_A), LCTL(LALT(KC_DEL)), RSFT(KC_A), RCTL(RGUI(KC_1)).
+        for keycode in [0x0204, 0x054C, 0x1204, 0x191E] {
+            assert!(round_trips(keycode), "{:#06x}", keycode);
+        }
+        let shifted = vec![
+            KeyboardMapEntrant::Keyboard(Keyboard::LeftShift),
+            KeyboardMapEntrant::Keyboard(Keyboard::A),
+        ];
+        assert!(usb_hid_of(0x0204) == Some(shifted));
+
+        // a lone chord of modifiers is its last modifier with the rest held.
+        let chord = [
+            KeyboardMapEntrant::Keyboard(Keyboard::LeftControl),
+            KeyboardMapEntrant::Keyboard(Keyboard::LeftShift),
+        ];
+        assert!(keycode_of(&chord) == 0x01E1);
+        assert!(round_trips(0x01E1));
+    }
+
+    #[test]
+    fn rejects_unmapped_keycodes() {
+        // KC_NO, KC_TRNS, a consumer keycode without a usage, past the basic
+        // keycodes, a modified key past the keyboard page, a layer keycode.
+        for keycode in [0x0000, 0x0001, 0x00AF, 0x00E8, 0x02F0, 0x5220] {
+            assert!(usb_hid_of(keycode).is_none(), "{:#06x}", keycode);
+        }
+    }
+
+    #[test]
+    fn unmappable_keys_read_as_kc_no() {
+        let a = KeyboardMapEntrant::Keyboard(Keyboard::A);
+        let b = KeyboardMapEntrant::Keyboard(Keyboard::B);
+        let left_shift = KeyboardMapEntrant::Keyboard(Keyboard::LeftShift);
+        let right_control = KeyboardMapEntrant::Keyboard(Keyboard::RightControl);
+        let mute = KeyboardMapEntrant::Consumer(Consumer::Mute);
+
+        for usb_hid in [
+            vec![],
+            // two keys, left and right modifiers together, a media key with
+            // a keyboard usage, a consumer usage qmk has no keycode for.
+            vec![a.clone(), b],
+            vec![left_shift, right_control, a.clone()],
+            vec![mute, a],
+            vec![KeyboardMapEntrant::Consumer(Consumer(0x0030))],
+        ] {
+            assert!(keycode_of(&usb_hid) == KC_NO);
+        }
+    }
+}
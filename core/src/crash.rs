@@ -0,0 +1,195 @@
+//! what the firmware was doing when it last went down: the panic message (or
+//! the faulting pc) and the last few scans, kept in ram that isn't cleared on
+//! reset so the next boot can hand it to the host over the configuration
+//! channel (`GET_CRASH_LOG`).
+//!
+//! the log as sent, see `CrashLog::encode`:
+//!
+//! | bytes   | field                                               |
+//! |---------|-----------------------------------------------------|
+//! | 1       | `CrashReason`                                       |
+//! | 1       | message length                                      |
+//! | n       | message, utf-8                                      |
+//! | 1       | event count                                         |
+//! | 8 each  | the scans, oldest first: modifiers, key count, keys |
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+const CRASH_MAGIC: u32 = 0xA2C2_A5E1;
+pub const CRASH_MESSAGE_LEN: usize = 160;
+pub const CRASH_EVENTS: usize = 16;
+/// the held keys a scan record has room for.
+pub const EVENT_KEYS: usize = 6;
+const EVENT_LEN: usize = 2 + EVENT_KEYS;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CrashReason {
+    Panic = 0x01,
+    HardFault = 0x02,
+}
+
+impl CrashReason {
+    pub fn get(reason: u8) -> Option<CrashReason> {
+        match reason {
+            0x01 => Some(CrashReason::Panic),
+            0x02 => Some(CrashReason::HardFault),
+            _ => None,
+        }
+    }
+}
+
+/// a debounced scan: the modifier chord and up to `EVENT_KEYS` scan codes.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[repr(C)]
+pub struct ScanRecord {
+    pub modifiers: u8,
+    /// every held key, even those past `EVENT_KEYS`.
+    pub key_count: u8,
+    pub keys: [u8; EVENT_KEYS],
+}
+
+/// the log as it sits in ram. every field is a plain integer so whatever the
+/// ram held at power on is a valid, if meaningless, `CrashLog`, `CRASH_MAGIC`
+/// tells the two apart.
+#[repr(C)]
+pub struct CrashLog {
+    magic: u32,
+    /// a `CrashReason`, 0 until something goes wrong.
+    reason: u8,
+    message_len: u8,
+    message: [u8; CRASH_MESSAGE_LEN],
+    next_event: u8,
+    event_count: u8,
+    events: [ScanRecord; CRASH_EVENTS],
+}
+
+impl CrashLog {
+    /// clear the log for this boot.
+    pub fn reset(&mut self) {
+        self.magic = CRASH_MAGIC;
+        self.reason = 0;
+        self.message_len = 0;
+        self.next_event = 0;
+        self.event_count = 0;
+    }
+
+    /// the encoded log of the previous boot if it crashed, then clear it.
+    pub fn take(&mut self) -> Option<Vec<u8>> {
+        let crashed = self.magic == CRASH_MAGIC
+            && CrashReason::get(self.reason).is_some()
+            && (self.message_len as usize) <= CRASH_MESSAGE_LEN
+            && (self.next_event as usize) < CRASH_EVENTS
+            && (self.event_count as usize) <= CRASH_EVENTS;
+        let log = crashed.then(|| self.encode());
+        self.reset();
+        log
+    }
+
+    /// note a scan, unless it's the same as the last one.
+    pub fn record_scan(&mut self, modifiers: u8, keys: impl Iterator<Item = u8>) {
+        let mut record = ScanRecord {
+            modifiers,
+            ..ScanRecord::default()
+        };
+        for key in keys {
+            if let Some(slot) = record.keys.get_mut(record.key_count as usize) {
+                *slot = key;
+            }
+            record.key_count = record.key_count.saturating_add(1);
+        }
+
+        let last = (self.next_event as usize + CRASH_EVENTS - 1) % CRASH_EVENTS;
+        if self.event_count > 0 && self.events[last] == record {
+            return;
+        }
+        self.events[self.next_event as usize] = record;
+        self.next_event = ((self.next_event as usize + 1) % CRASH_EVENTS) as u8;
+        self.event_count = (self.event_count + 1).min(CRASH_EVENTS as u8);
+    }
+
+    /// note what went wrong, truncating the message to `CRASH_MESSAGE_LEN`.
+    pub fn record_crash(&mut self, reason: CrashReason, message: fmt::Arguments) {
+        self.reason = reason as u8;
+        let mut writer = MessageWriter {
+            message: &mut self.message,
+            len: 0,
+        };
+        writer.write_fmt(message).ok();
+        self.message_len = writer.len as u8;
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut log = Vec::new();
+        log.push(self.reason);
+        log.push(self.message_len);
+        log.extend_from_slice(&self.message[..self.message_len as usize]);
+        log.push(self.event_count);
+
+        let oldest =
+            (self.next_event as usize + CRASH_EVENTS - self.event_count as usize) % CRASH_EVENTS;
+        for index in 0..self.event_count as usize {
+            let event = &self.events[(oldest + index) % CRASH_EVENTS];
+            log.push(event.modifiers);
+            log.push(event.key_count);
+            log.extend_from_slice(&event.keys);
+        }
+        log
+    }
+}
+
+/// a crash log as the host reads it back.
+pub struct CrashReport {
+    pub reason: CrashReason,
+    pub message: String,
+    pub events: Vec<ScanRecord>,
+}
+
+impl CrashReport {
+    pub fn decode(log: &[u8]) -> Option<CrashReport> {
+        let (reason, rest) = log.split_first()?;
+        let (message_len, rest) = rest.split_first()?;
+        let message = rest.get(..*message_len as usize)?;
+        let (event_count, events) = rest[*message_len as usize..].split_first()?;
+
+        let events = events
+            .chunks_exact(EVENT_LEN)
+            .take(*event_count as usize)
+            .map(|event| ScanRecord {
+                modifiers: event[0],
+                key_count: event[1],
+                keys: event[2..].try_into().unwrap(),
+            })
+            .collect::<Vec<_>>();
+        if events.len() != *event_count as usize {
+            return None;
+        }
+
+        Some(CrashReport {
+            reason: CrashReason::get(*reason)?,
+            message: String::from_utf8_lossy(message).into_owned(),
+            events,
+        })
+    }
+}
+
+struct MessageWriter<'a> {
+    message: &'a mut [u8; CRASH_MESSAGE_LEN],
+    len: usize,
+}
+
+impl Write for MessageWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = CRASH_MESSAGE_LEN - self.len;
+        // don't split a character when truncating.
+        let mut take = s.len().min(room);
+        while !s.is_char_boundary(take) {
+            take -= 1;
+        }
+        self.message[self.len..self.len + take].copy_from_slice(&s.as_bytes()[..take]);
+        self.len += take;
+        Ok(())
+    }
+}
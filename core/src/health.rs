@@ -0,0 +1,60 @@
+//! how the firmware is faring, for the host to read over the configuration
+//! channel (`GET_HEALTH`).
+
+/// what reset the board into this boot.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ResetReason {
+    PowerOn = 0x01,
+    /// the RUN pin, i.e. a reset button.
+    RunPin = 0x02,
+    /// a debugger, through the rescue dp.
+    Debugger = 0x03,
+    /// the scan loop stopped feeding the watchdog, or a crash, see `crash`.
+    Watchdog = 0x04,
+    /// the rom bootloader handing back, after a uf2 was written.
+    Bootloader = 0x05,
+}
+
+impl ResetReason {
+    pub fn get(reason: u8) -> Option<ResetReason> {
+        match reason {
+            0x01 => Some(ResetReason::PowerOn),
+            0x02 => Some(ResetReason::RunPin),
+            0x03 => Some(ResetReason::Debugger),
+            0x04 => Some(ResetReason::Watchdog),
+            0x05 => Some(ResetReason::Bootloader),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ResetReason::PowerOn => "power on",
+            ResetReason::RunPin => "run pin",
+            ResetReason::Debugger => "debugger",
+            ResetReason::Watchdog => "watchdog",
+            ResetReason::Bootloader => "bootloader",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Health {
+    pub reset_reason: ResetReason,
+    pub uptime_ms: u32,
+    /// scan periods that went by without a scan of their own, the scan loop
+    /// was still busy with the one before.
+    pub scan_overruns: u32,
+}
+
+impl Health {
+    pub fn new(reset_reason: ResetReason) -> Self {
+        Self {
+            reset_reason,
+            uptime_ms: 0,
+            scan_overruns: 0,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! chords the firmware acts on itself, matched on the debounced modifier lines
+//! ahead of layer resolution so that no keymap can shadow them.
+
+use crate::kb::input::Modifiers;
+
+/// control + open apple + closed apple + reset, held for `BOOTLOADER_HOLD_MS`
+/// to reboot into the rp2040's usb bootloader.
+pub const BOOTLOADER_CHORD: Modifiers = Modifiers::from_bits_truncate(
+    Modifiers::CONTROL.bits()
+        | Modifiers::OPEN_APPLE.bits()
+        | Modifiers::CLOSED_APPLE.bits()
+        | Modifiers::RESET.bits(),
+);
+pub const BOOTLOADER_HOLD_MS: u32 = 2_000;
+
+/// times how long a chord has been held, exactly and without a break. the
+/// time comes from the caller's clock rather than a count of scans, which
+/// merge when the core handling them falls behind.
+pub struct ChordHold {
+    chord: Modifiers,
+    hold_ms: u32,
+    /// when the chord went down, `None` while it isn't held.
+    held_since: Option<u32>,
+    fired: bool,
+}
+
+impl ChordHold {
+    pub const fn new(chord: Modifiers, hold_ms: u32) -> Self {
+        Self {
+            chord,
+            hold_ms,
+            held_since: None,
+            fired: false,
+        }
+    }
+
+    /// whether the hold completes at `now_ms`, true only once per hold.
+    pub fn tick(&mut self, modifiers: Modifiers, now_ms: u32) -> bool {
+        if modifiers != self.chord {
+            self.held_since = None;
+            self.fired = false;
+            return false;
+        }
+        let held_since = *self.held_since.get_or_insert(now_ms);
+        if self.fired || now_ms.wrapping_sub(held_since) < self.hold_ms {
+            return false;
+        }
+        self.fired = true;
+        true
+    }
+}
@@ -0,0 +1,310 @@
+//! A simple-as-possible key debouncer module to reduce undesired duplicate keypress
+//! reports.
+
+/// How a key's raw samples become its debounced state.
+///
+/// Worn IIe key switches and the Apple key switches chatter differently, so the
+/// matrix and the modifier lines each get a `DebounceConfig` of their own.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum DebounceMode {
+    /// A press is reported immediately, a release only once the key has read
+    /// released for `release_ticks` (counting the last pressed tick).
+    EagerPress = 0x00,
+    /// Both edges are deferred: a change is reported once the key has read
+    /// the new state for `press_ticks` or `release_ticks` in a row.
+    Deferred = 0x01,
+    /// A per-key integrator counting up while the key reads the other state
+    /// than reported and down while it doesn't, reporting the change at
+    /// `press_ticks` or `release_ticks`. Chatter slows it down instead of
+    /// restarting it.
+    Symmetric = 0x02,
+}
+
+impl DebounceMode {
+    pub fn get(value: u16) -> Option<DebounceMode> {
+        match value {
+            0x00 => Some(DebounceMode::EagerPress),
+            0x01 => Some(DebounceMode::Deferred),
+            0x02 => Some(DebounceMode::Symmetric),
+            _ => None,
+        }
+    }
+}
+
+/// The algorithm and windows, in ticks, of a group of keys.
+#[derive(Clone, Copy)]
+pub struct DebounceConfig {
+    pub mode: DebounceMode,
+    pub press_ticks: u8,
+    pub release_ticks: u8,
+}
+
+impl DebounceConfig {
+    /// The original eager scheme, a re-press within `expiration_ticks` of a
+    /// release is one continuous keypress.
+    pub const fn eager(expiration_ticks: u8) -> Self {
+        Self {
+            mode: DebounceMode::EagerPress,
+            press_ticks: 0,
+            release_ticks: expiration_ticks,
+        }
+    }
+}
+
+/// The debounce state of a single key or modifier line.
+#[derive(Clone, Copy, Default)]
+struct KeyDebounce {
+    /// The state last reported.
+    reported: bool,
+    /// A countdown, a run length or an integrator, depending on the mode.
+    count: u8,
+}
+
+impl KeyDebounce {
+    fn tick(&mut self, pressed: bool, config: &DebounceConfig) -> bool {
+        match config.mode {
+            DebounceMode::EagerPress => {
+                // A window of 0 would never report the press at all.
+                self.count = if pressed {
+                    config.release_ticks.max(1)
+                } else {
+                    self.count.saturating_sub(1)
+                };
+                self.reported = self.count != 0;
+            }
+            DebounceMode::Deferred => {
+                if pressed == self.reported {
+                    self.count = 0;
+                } else {
+                    self.count = self.count.saturating_add(1);
+                    let window = if pressed {
+                        config.press_ticks
+                    } else {
+                        config.release_ticks
+                    };
+                    if self.count >= window {
+                        self.reported = pressed;
+                        self.count = 0;
+                    }
+                }
+            }
+            DebounceMode::Symmetric => {
+                // A sample agreeing with the reported state takes a step back
+                // rather than clearing the count.
+                self.count = if pressed == self.reported {
+                    self.count.saturating_sub(1)
+                } else {
+                    self.count.saturating_add(1)
+                };
+                let window = if self.reported {
+                    config.release_ticks
+                } else {
+                    config.press_ticks
+                };
+                if self.count >= window.max(1) {
+                    self.reported = pressed;
+                    self.count = 0;
+                }
+            }
+        }
+        self.reported
+    }
+}
+
+/// `Debounce` is a tick-based allocation-free debouncer, keeping the state of
+/// every key and modifier line.
+///
+/// # Algorithm
+/// Each key runs the `DebounceMode` of its group's `DebounceConfig`, one for the
+/// key matrix and one for the modifier lines. `Debounce::new` gives both the
+/// original "eager" scheme (reports keypresses immediately), which prevents rapid
+/// double-keypress events (i.e. when a key is reported as not pressed, then
+/// immediately re-pressed).
+///
+/// # Ticks
+/// Ticks are unitless, and represent one call to `report_and_tick()`. For example,
+/// if `report_and_tick()` is called at an interval of 1ms with a release window of
+/// 5 ticks, a key will not be reported as a re-press for 5ms.
+pub struct Debounce<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize> {
+    /// The debounce state of each key.
+    matrix: [[KeyDebounce; NUM_ROWS]; NUM_COLS],
+
+    /// The debounce state of each modifier line.
+    mods: [KeyDebounce; NUM_MODS],
+
+    keys_config: DebounceConfig,
+    mods_config: DebounceConfig,
+}
+
+impl<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize>
+    Debounce<NUM_MODS, NUM_ROWS, NUM_COLS>
+{
+    /// Create an eager `Debounce` with a specified expiration tick amount.
+    /// See struct documentation for what a "tick" means in this Debouncer.
+    pub fn new(expiration_ticks: u8) -> Self {
+        let config = DebounceConfig::eager(expiration_ticks);
+        Self::with_configs(config, config)
+    }
+
+    /// Create a `Debounce` with separate configurations for the key matrix and
+    /// the modifier lines.
+    pub fn with_configs(keys_config: DebounceConfig, mods_config: DebounceConfig) -> Self {
+        Self {
+            matrix: [[KeyDebounce::default(); NUM_ROWS]; NUM_COLS],
+            mods: [KeyDebounce::default(); NUM_MODS],
+            keys_config,
+            mods_config,
+        }
+    }
+
+    /// Report a new raw key scan matrix, expected to be called at a periodic "tick rate"
+    /// corresponding to the same debouncing tick amounts specified in the
+    /// constructor.
+    pub fn report_and_tick(
+        &mut self,
+        modifier_dimm: &[bool; NUM_MODS],
+        report_matrix: &[[bool; NUM_ROWS]; NUM_COLS],
+    ) -> ([bool; NUM_MODS], [[bool; NUM_ROWS]; NUM_COLS]) {
+        let mut debounced_dimm = [false; NUM_MODS];
+
+        for key in 0..NUM_MODS {
+            debounced_dimm[key] = self.mods[key].tick(modifier_dimm[key], &self.mods_config);
+        }
+
+        let mut debounced_matrix = [[false; NUM_ROWS]; NUM_COLS];
+
+        // Things got a bit hairy with iterators, writing this way for legibility.
+        for col in 0..NUM_COLS {
+            for row in 0..NUM_ROWS {
+                debounced_matrix[col][row] =
+                    self.matrix[col][row].tick(report_matrix[col][row], &self.keys_config);
+            }
+        }
+
+        (debounced_dimm, debounced_matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const EAGER: DebounceConfig = DebounceConfig::eager(3);
+    const DEFERRED: DebounceConfig = DebounceConfig {
+        mode: DebounceMode::Deferred,
+        press_ticks: 2,
+        release_ticks: 3,
+    };
+    const SYMMETRIC: DebounceConfig = DebounceConfig {
+        mode: DebounceMode::Symmetric,
+        press_ticks: 3,
+        release_ticks: 3,
+    };
+
+    /// The state reported after each of `samples`, starting from released.
+    fn run(config: DebounceConfig, samples: &[u8]) -> Vec<u8> {
+        let mut key = KeyDebounce::default();
+        samples
+            .iter()
+            .map(|sample| key.tick(*sample != 0, &config) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn eager_press_is_immediate() {
+        assert_eq!(run(EAGER, &[1, 1]), [1, 1]);
+    }
+
+    #[test]
+    fn eager_release_waits_out_the_window() {
+        assert_eq!(run(EAGER, &[1, 0, 0, 0, 0]), [1, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn eager_holds_through_chatter() {
+        assert_eq!(
+            run(EAGER, &[1, 0, 1, 0, 0, 1, 0, 0, 0]),
+            [1, 1, 1, 1, 1, 1, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn eager_zero_window_still_presses() {
+        assert_eq!(run(DebounceConfig::eager(0), &[1, 0]), [1, 0]);
+    }
+
+    #[test]
+    fn deferred_press_waits_out_the_window() {
+        assert_eq!(run(DEFERRED, &[1, 1, 1]), [0, 1, 1]);
+    }
+
+    #[test]
+    fn deferred_release_waits_out_the_window() {
+        assert_eq!(run(DEFERRED, &[1, 1, 0, 0, 0]), [0, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn deferred_chatter_restarts_the_window() {
+        assert_eq!(run(DEFERRED, &[1, 0, 1, 0, 1, 1]), [0, 0, 0, 0, 0, 1]);
+        assert_eq!(
+            run(DEFERRED, &[1, 1, 0, 0, 1, 0, 0, 0]),
+            [0, 1, 1, 1, 1, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn symmetric_press_waits_out_the_window() {
+        assert_eq!(run(SYMMETRIC, &[1, 1, 1, 1]), [0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn symmetric_release_waits_out_the_window() {
+        assert_eq!(run(SYMMETRIC, &[1, 1, 1, 0, 0, 0]), [0, 0, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn symmetric_edges_take_their_own_window() {
+        let config = DebounceConfig {
+            release_ticks: 5,
+            ..SYMMETRIC
+        };
+        assert_eq!(
+            run(config, &[1, 1, 1, 0, 0, 0, 0, 0]),
+            [0, 0, 1, 1, 1, 1, 1, 0]
+        );
+        // A bounce during the release costs it a tick back too.
+        let config = DebounceConfig {
+            press_ticks: 1,
+            release_ticks: 2,
+            ..SYMMETRIC
+        };
+        assert_eq!(run(config, &[1, 0, 1, 0, 0]), [1, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn symmetric_chatter_slows_rather_than_restarts() {
+        // A bounce costs the press one tick back, not the whole window.
+        assert_eq!(run(SYMMETRIC, &[1, 1, 0, 1, 1]), [0, 0, 0, 0, 1]);
+        // And a bounce while held doesn't release it.
+        assert_eq!(
+            run(SYMMETRIC, &[1, 1, 1, 0, 1, 0, 0, 0]),
+            [0, 0, 1, 1, 1, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn groups_take_their_own_config() {
+        let mut debounce: Debounce<1, 1, 1> = Debounce::with_configs(DEFERRED, EAGER);
+        assert_eq!(
+            debounce.report_and_tick(&[true], &[[true]]),
+            ([true], [[false]])
+        );
+        assert_eq!(
+            debounce.report_and_tick(&[true], &[[true]]),
+            ([true], [[true]])
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! the debounced scan as a stream of changes, so that the core scanning the
+//! matrix and the core running the oracle only have to pass words between
+//! them (the rp2040's sio fifo), see `KeyScan::changes` and `KeyScan::apply`.
+//!
+//! | bits   | field                                     |
+//! |--------|-------------------------------------------|
+//! | 24..32 | kind: 1 key, 2 modifier line, 3 scan end  |
+//! | 8..16  | scan code, or index into `MODIFIER_LINES` |
+//! | 0      | pressed, or ghosted for a scan end        |
+
+const KIND_KEY: u32 = 0x01;
+const KIND_MODIFIER: u32 = 0x02;
+const KIND_SCAN_END: u32 = 0x03;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ScanEvent {
+    /// a key of the matrix went down or came up.
+    Key { scan_code: u8, pressed: bool },
+    /// a modifier line, by its index into `MODIFIER_LINES`.
+    Modifier { line: u8, pressed: bool },
+    /// every change of a scan has been sent, see `KeyScan::ghosted`.
+    ScanEnd { ghosted: bool },
+}
+
+impl ScanEvent {
+    pub fn to_word(self) -> u32 {
+        match self {
+            ScanEvent::Key { scan_code, pressed } => {
+                KIND_KEY << 24 | (scan_code as u32) << 8 | pressed as u32
+            }
+            ScanEvent::Modifier { line, pressed } => {
+                KIND_MODIFIER << 24 | (line as u32) << 8 | pressed as u32
+            }
+            ScanEvent::ScanEnd { ghosted } => KIND_SCAN_END << 24 | ghosted as u32,
+        }
+    }
+
+    pub fn from_word(word: u32) -> Option<ScanEvent> {
+        let index = (word >> 8) as u8;
+        let pressed = word & 0x01 != 0;
+        match word >> 24 {
+            KIND_KEY => Some(ScanEvent::Key {
+                scan_code: index,
+                pressed,
+            }),
+            KIND_MODIFIER => Some(ScanEvent::Modifier {
+                line: index,
+                pressed,
+            }),
+            KIND_SCAN_END => Some(ScanEvent::ScanEnd { ghosted: pressed }),
+            _ => None,
+        }
+    }
+}
@@ -1,8 +1,7 @@
-use defmt::Format;
-
 #[allow(unused)]
 #[repr(u8)]
-#[derive(Copy, Clone, Format, PartialEq)]
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum KeyCode {
     Empty = 0x0,
     A = 0x04,
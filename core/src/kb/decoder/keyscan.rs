@@ -0,0 +1,472 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+use crate::kb::input::Modifiers;
+
+use super::debounce::Debounce;
+use super::event::ScanEvent;
+use super::MODIFIER_LINES;
+
+#[derive(Clone, Copy)]
+pub struct KeyScan<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize> {
+    matrix: [[bool; NUM_ROWS]; NUM_COLS],
+    mods: [bool; NUM_MODS],
+    /// keys were held back by `suppress_ghosts`.
+    ghosted: bool,
+}
+
+pub enum KeyScanDecoder {
+    Modifiers(Vec<u8>),
+    Characters(Vec<u8>),
+}
+
+impl Into<Vec<u8>> for KeyScanDecoder {
+    fn into(self) -> Vec<u8> {
+        match self {
+            Self::Modifiers(modifiers) => modifiers,
+            Self::Characters(characters) => characters,
+        }
+    }
+}
+
+/*
+impl<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize> Deref for KeyScan<NUM_ROWS, NUM_COLS> {
+    type Target = [[bool; NUM_ROWS]; NUM_COLS];
+
+    fn deref(&self) -> &Self::Target {
+        &self.matrix
+    }
+}
+*/
+
+impl<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize>
+    KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>
+{
+    pub fn into_decoder(self) -> (KeyScanDecoder, KeyScanDecoder) {
+        let modifiers = self
+            .mods
+            .iter()
+            .enumerate()
+            .fold(vec![], |mut acc, (modifier, key)| {
+                if *key {
+                    let scan_code = MODIFIER_LINES
+                        .get(modifier)
+                        .copied()
+                        .unwrap_or(Modifiers::BARE)
+                        .bits();
+
+                    acc.push(scan_code);
+                }
+                acc
+            });
+
+        let characters =
+            self.matrix
+                .iter()
+                .enumerate()
+                .fold(vec![], |mut acc, (col, matrix_col)| {
+                    for (row, matrix_row) in matrix_col.iter().enumerate() {
+                        if *matrix_row {
+                            acc.push(((col * 16) + row) as u8);
+                        }
+                    }
+                    acc
+                });
+
+        (
+            KeyScanDecoder::Modifiers(modifiers),
+            KeyScanDecoder::Characters(characters),
+        )
+    }
+    /// nothing held.
+    pub fn released() -> Self {
+        Self {
+            matrix: [[false; NUM_ROWS]; NUM_COLS],
+            mods: [false; NUM_MODS],
+            ghosted: false,
+        }
+    }
+
+    /// whether the scan is in the phantom state, see `suppress_ghosts`.
+    pub fn ghosted(&self) -> bool {
+        self.ghosted
+    }
+
+    /// the keys on the corners of a rectangle. the IIe matrix has no diodes,
+    /// so three held corners read as the fourth as well and there's no
+    /// telling which of the four is the phantom.
+    pub fn ghosts(&self) -> [[bool; NUM_ROWS]; NUM_COLS] {
+        let mut ghosts = [[false; NUM_ROWS]; NUM_COLS];
+        for first in 0..NUM_COLS {
+            for second in first + 1..NUM_COLS {
+                let shared = (0..NUM_ROWS)
+                    .filter(|row| self.matrix[first][*row] && self.matrix[second][*row]);
+                if shared.clone().count() < 2 {
+                    continue;
+                }
+                for row in shared {
+                    ghosts[first][row] = true;
+                    ghosts[second][row] = true;
+                }
+            }
+        }
+        ghosts
+    }
+
+    /// hold every key of `ghosts` at its state in `previous`, the last scan
+    /// passed on, rather than type a phantom key. the scan is flagged as
+    /// `ghosted` for as long as a rectangle is held, so the reports can say
+    /// so the way hid does: `ERROR_ROLL_OVER`.
+    pub fn suppress_ghosts(&mut self, previous: &Self) {
+        let ghosts = self.ghosts();
+        self.ghosted = false;
+        for ((matrix_col, previous_col), ghost_col) in self
+            .matrix
+            .iter_mut()
+            .zip(previous.matrix.iter())
+            .zip(ghosts.iter())
+        {
+            for ((pressed, was_pressed), ghost) in matrix_col
+                .iter_mut()
+                .zip(previous_col.iter())
+                .zip(ghost_col.iter())
+            {
+                if *ghost {
+                    *pressed = *was_pressed;
+                    self.ghosted = true;
+                }
+            }
+        }
+    }
+
+    /// what changed since `previous`, modifier lines first. the end of the
+    /// scan is left to the caller.
+    pub fn changes<'a>(&'a self, previous: &'a Self) -> impl Iterator<Item = ScanEvent> + 'a {
+        let modifiers = self
+            .mods
+            .iter()
+            .zip(previous.mods.iter())
+            .enumerate()
+            .filter(|(_, (held, was_held))| held != was_held)
+            .map(|(line, (held, _))| ScanEvent::Modifier {
+                line: line as u8,
+                pressed: *held,
+            });
+        let keys = self
+            .matrix
+            .iter()
+            .flatten()
+            .zip(previous.matrix.iter().flatten())
+            .enumerate()
+            .filter(|(_, (pressed, was_pressed))| pressed != was_pressed)
+            .map(|(key, (pressed, _))| ScanEvent::Key {
+                scan_code: (((key / NUM_ROWS) * 16) + key % NUM_ROWS) as u8,
+                pressed: *pressed,
+            });
+        modifiers.chain(keys)
+    }
+
+    /// bring a copy of the scan up to date with a change from `changes`.
+    pub fn apply(&mut self, event: ScanEvent) {
+        match event {
+            ScanEvent::Key { scan_code, pressed } => {
+                let (col, row) = ((scan_code / 16) as usize, (scan_code % 16) as usize);
+                if let Some(key) = self.matrix.get_mut(col).and_then(|col| col.get_mut(row)) {
+                    *key = pressed;
+                }
+            }
+            ScanEvent::Modifier { line, pressed } => {
+                if let Some(modifier) = self.mods.get_mut(line as usize) {
+                    *modifier = pressed;
+                }
+            }
+            ScanEvent::ScanEnd { ghosted } => self.ghosted = ghosted,
+        }
+    }
+
+    /// the chord of the debounced modifier lines.
+    pub fn modifiers(&self) -> Modifiers {
+        self.mods
+            .iter()
+            .zip(MODIFIER_LINES.iter())
+            .filter(|(held, _)| **held)
+            .fold(Modifiers::BARE, |chord, (_, modifier)| chord | *modifier)
+    }
+
+    /// the scan codes of the held keys, as `into_decoder` has them.
+    pub fn scan_codes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.matrix
+            .iter()
+            .enumerate()
+            .flat_map(|(col, matrix_col)| {
+                matrix_col
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pressed)| **pressed)
+                    .map(move |(row, _)| ((col * 16) + row) as u8)
+            })
+    }
+
+    /// Debounce a raw sample of the modifier lines and key matrix into a `KeyScan`.
+    ///
+    /// this is the hardware independent half of `KeyScan::scan` - anything that can
+    /// produce a raw matrix (gpio, a script on the host, ...) can feed the decoder.
+    pub fn from_raw(
+        raw_modifiers: &[bool; NUM_MODS],
+        raw_matrix: &[[bool; NUM_ROWS]; NUM_COLS],
+        debounce: &mut Debounce<NUM_MODS, NUM_ROWS, NUM_COLS>,
+    ) -> Self {
+        let (mods, matrix) = debounce.report_and_tick(raw_modifiers, raw_matrix);
+        Self {
+            mods,
+            matrix,
+            ghosted: false,
+        }
+    }
+
+    /// Strobe each column and sample the rows and modifier lines without debouncing.
+    pub fn sample(
+        modifiers: (
+            &[&dyn InputPin<Error = Infallible>],
+            &[&dyn InputPin<Error = Infallible>],
+        ),
+        rows: &[&dyn InputPin<Error = Infallible>],
+        columns: &mut [&mut dyn OutputPin<Error = Infallible>],
+        delay: &mut dyn DelayUs<u32>,
+    ) -> ([bool; NUM_MODS], [[bool; NUM_ROWS]; NUM_COLS]) {
+        let mut raw_matrix = [[false; NUM_ROWS]; NUM_COLS];
+        let mut raw_modifiers = [false; NUM_MODS];
+
+        let (input_modifiers, output_modifiers) = modifiers;
+        for (key, gpio_key) in input_modifiers.iter().enumerate() {
+            raw_modifiers[key] = gpio_key.is_high().unwrap();
+        }
+        for (key, gpio_key) in output_modifiers.iter().enumerate() {
+            raw_modifiers[key + input_modifiers.len()] = gpio_key.is_low().unwrap();
+        }
+
+        for (gpio_col, matrix_col) in columns.iter_mut().zip(raw_matrix.iter_mut()) {
+            gpio_col.set_high().unwrap();
+            delay.delay_us(60);
+
+            for (gpio_row, matrix_row) in rows.iter().zip(matrix_col.iter_mut()) {
+                *matrix_row = gpio_row.is_high().unwrap();
+            }
+
+            gpio_col.set_low().unwrap();
+            delay.delay_us(60);
+        }
+
+        (raw_modifiers, raw_matrix)
+    }
+
+    pub fn scan(
+        modifiers: (
+            &[&dyn InputPin<Error = Infallible>],
+            &[&dyn InputPin<Error = Infallible>],
+        ),
+        rows: &[&dyn InputPin<Error = Infallible>],
+        columns: &mut [&mut dyn OutputPin<Error = Infallible>],
+        delay: &mut dyn DelayUs<u32>,
+        debounce: &mut Debounce<NUM_MODS, NUM_ROWS, NUM_COLS>,
+    ) -> Self {
+        let (raw_modifiers, raw_matrix) = Self::sample(modifiers, rows, columns, delay);
+        Self::from_raw(&raw_modifiers, &raw_matrix, debounce)
+    }
+}
+
+impl<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize>
+    From<KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>> for Vec<Vec<u8>>
+{
+    fn from(value: KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>) -> Self {
+        let mut matrix = value.matrix.clone();
+
+        let scan_code_matrix = matrix.iter_mut().enumerate().map(|(col, matrix_col)| {
+            return matrix_col
+                .iter_mut()
+                .enumerate()
+                .map(|(row, matrix_row)| {
+                    if *matrix_row {
+                        ((col * 16) + row) as u8
+                    } else {
+                        0
+                    }
+                })
+                .collect();
+        });
+
+        let scan_codes = scan_code_matrix.collect();
+
+        scan_codes
+    }
+}
+
+/*
+impl<const NUM_MODS: usize, const NUM_ROWS: usize, const NUM_COLS: usize>
+    From<KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>> for Vec<KeyboardReport>
+{
+    fn from(scan: KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>) -> Self {
+        let mut keycodes = vec![];
+        let mut modifier = 0;
+
+        // First scan for any function keys being pressed
+        let layer_mapping = key_mapping::NORMAL_LAYER_MAPPING;
+
+        let mut mods_mapping: Vec<u8> = [].to_vec();
+        for (idx, (key_pressed, mapping_key)) in
+            scan.mods.iter().zip(mods_mapping.iter_mut()).enumerate()
+        {
+            if *key_pressed {
+                *mapping_key = match idx {
+                    0 => 0x80, // SW1 ::  Closed Apple
+                    1 => 0x40, // SW0 :: Open Apple
+                    2 => 0x20, // Control
+                    3 => 0x40, // RESET
+                    4 => 0x40, // Shift
+                    _ => 0x0,
+                }
+            }
+        }
+
+        /*
+         */
+        let mut key_codes: Vec<u8> = Vec::new();
+        let generate = |col: usize, row: usize| {
+            let _a = 2;
+            key_codes.push(1);
+        };
+
+        // Second scan to generate the correct keycodes given the activated key map
+        for (col, matrix_column) in scan.matrix.iter().enumerate() {
+            for (row, key_pressed) in matrix_column.iter().enumerate() {
+                if *key_pressed {
+                    generate(col, row);
+                }
+            }
+        }
+
+        // print matrix in hex
+        /*
+        info!("---");
+        for (i, d) in raw_matrix.iter().enumerate() {
+            let nums: Vec<u8> = d.iter().map(|&x| x.into()).collect();
+            info!("{} ::: {}", i, nums.as_slice());
+        }
+        info!("---");
+        */
+
+        vec![KeyboardReport {
+            modifier,
+            reserved: 0,
+            leds: 0,
+            keycodes,
+        }]
+    }
+}
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Scan = KeyScan<1, 4, 4>;
+
+    /// a scan holding `keys`, as `(column, row)`.
+    fn scan(keys: &[(usize, usize)]) -> Scan {
+        let mut scan = Scan::released();
+        for (col, row) in keys {
+            scan.matrix[*col][*row] = true;
+        }
+        scan
+    }
+
+    fn scan_of(matrix: [[bool; 4]; 4]) -> Scan {
+        let mut scan = Scan::released();
+        scan.matrix = matrix;
+        scan
+    }
+
+    fn held(scan: &Scan) -> Vec<(usize, usize)> {
+        let mut keys = Vec::new();
+        for (col, matrix_col) in scan.matrix.iter().enumerate() {
+            for (row, pressed) in matrix_col.iter().enumerate() {
+                if *pressed {
+                    keys.push((col, row));
+                }
+            }
+        }
+        keys
+    }
+
+    const RECTANGLE: [(usize, usize); 4] = [(0, 0), (0, 1), (1, 0), (1, 1)];
+
+    #[test]
+    fn three_corners_read_with_their_phantom() {
+        // holding (0, 0), (0, 1) and (1, 0) reads (1, 1) too.
+        let previous = scan(&[(0, 0), (0, 1)]);
+        let mut current = scan(&RECTANGLE);
+        assert_eq!(held(&scan_of(current.ghosts())), RECTANGLE);
+
+        current.suppress_ghosts(&previous);
+        assert!(current.ghosted());
+        assert_eq!(held(&current), held(&previous));
+        assert_eq!(current.changes(&previous).count(), 0);
+    }
+
+    #[test]
+    fn four_corners_held() {
+        let mut current = scan(&RECTANGLE);
+        current.suppress_ghosts(&Scan::released());
+        assert!(current.ghosted());
+        assert!(held(&current).is_empty());
+    }
+
+    #[test]
+    fn two_rectangles_sharing_a_column() {
+        let keys = [
+            (0, 0),
+            (0, 1),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (1, 3),
+            (2, 2),
+            (2, 3),
+            // shares a single row with each column, not a rectangle.
+            (3, 0),
+        ];
+        let ghosts = held(&scan_of(scan(&keys).ghosts()));
+        assert_eq!(ghosts, keys[..8]);
+    }
+
+    #[test]
+    fn three_keys_in_a_row_arent_a_rectangle() {
+        let keys = [(0, 2), (1, 2), (2, 2)];
+        let mut current = scan(&keys);
+        assert!(held(&scan_of(current.ghosts())).is_empty());
+
+        current.suppress_ghosts(&Scan::released());
+        assert!(!current.ghosted());
+        assert_eq!(held(&current), keys);
+    }
+
+    #[test]
+    fn ghosted_keys_hold_their_previous_state() {
+        let previous = scan(&[(0, 0), (3, 3)]);
+        let mut current = scan(&[(0, 0), (0, 1), (1, 0), (1, 1), (2, 2), (3, 3)]);
+        current.suppress_ghosts(&previous);
+        assert!(current.ghosted());
+        // keys outside the rectangle still come and go.
+        assert_eq!(held(&current), [(0, 0), (2, 2), (3, 3)]);
+
+        // the rectangle broken, the scan goes through as read.
+        let previous = current;
+        let mut current = scan(&[(0, 1), (2, 2)]);
+        current.suppress_ghosts(&previous);
+        assert!(!current.ghosted());
+        assert_eq!(held(&current), [(0, 1), (2, 2)]);
+    }
+}
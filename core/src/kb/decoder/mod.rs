@@ -0,0 +1,26 @@
+mod chord;
+mod debounce;
+mod event;
+mod key_codes;
+mod key_mapping;
+mod keyscan;
+
+pub use chord::*;
+pub use debounce::*;
+pub use event::*;
+pub use keyscan::*;
+
+use crate::kb::input::Modifiers;
+
+pub const NUM_COLS: usize = 8;
+pub const NUM_ROWS: usize = 10;
+pub const NUM_MODS: usize = 5;
+
+/// the modifier each modifier line reports, in sampling order.
+pub const MODIFIER_LINES: [Modifiers; NUM_MODS] = [
+    Modifiers::CLOSED_APPLE, // SW1 :: Closed Apple
+    Modifiers::OPEN_APPLE,   // SW0 :: Open Apple
+    Modifiers::CONTROL,      // Control
+    Modifiers::RESET,        // RESET
+    Modifiers::SHIFT,        // Shift
+];
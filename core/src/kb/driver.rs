@@ -0,0 +1,339 @@
+use alloc::vec::Vec;
+
+use crate::shared::kb::*;
+
+use super::{
+    decoder::{KeyScan, NUM_COLS, NUM_MODS, NUM_ROWS},
+    input::Modify,
+    kbmap::KeyMap,
+    leds::{CapsLockSync, Leds},
+    oracle::KbOracleReports,
+    report::{KeyboardReport, NkroReport},
+    state::KeyState,
+};
+
+#[derive(Clone)]
+pub struct KbDriver {
+    pub key_map: KeyMap,
+    pub key_state: KeyState,
+    /// the host's lock leds as of its last output report.
+    pub leds: Leds,
+    caps_lock: CapsLockSync,
+    /// whether the host was last told the matrix is ghosted, see `roll_over`.
+    rolled_over: bool,
+    /// whether the host was last told nothing is held, the release is
+    /// reported once rather than every idle scan.
+    released: bool,
+}
+
+impl KbDriver {
+    /// a driver resolving scans against `key_map` rather than the built-in one.
+    pub fn with_key_map(key_map: KeyMap) -> KbDriver {
+        KbDriver {
+            key_map,
+            key_state: KeyState::init(),
+            leds: Leds::NONE,
+            caps_lock: CapsLockSync::new(),
+            rolled_over: false,
+            released: true,
+        }
+    }
+
+    /// the reports tapping caps lock for the host to follow the IIe's CAPS
+    /// LOCK latch at `now_ms`, see `CapsLockSync`.
+    pub fn sync_caps_lock(&mut self, latched: bool, now_ms: u32) -> Option<[KbOracleReports; 2]> {
+        self.caps_lock.sync(latched, self.leds, now_ms)
+    }
+
+    /// flag the keyboard reports of a ghosted scan as `roll_over`. the held
+    /// keys are reported every scan regardless, a report of its own is only
+    /// added when the matrix becomes ghosted, or stops being so, with nothing
+    /// else to carry it. only a boot protocol host sees the error, the nkro
+    /// report has no room for it (see `NkroReport::roll_over`).
+    fn roll_over(
+        &mut self,
+        mut reports: Vec<KbOracleReports>,
+        ghosted: bool,
+    ) -> Vec<KbOracleReports> {
+        let mut carried = false;
+        for report in reports.iter_mut() {
+            if let KbOracleReports::Keyboard(keyboard) = report {
+                keyboard.roll_over = ghosted;
+                carried = true;
+            }
+        }
+        if !carried && ghosted != self.rolled_over {
+            let mut keyboard = NkroReport::empty();
+            keyboard.roll_over = ghosted;
+            reports.push(KbOracleReports::Keyboard(keyboard));
+        }
+        self.rolled_over = ghosted;
+        reports
+    }
+}
+
+impl KeyboardDriver for KbDriver {
+    fn init() -> KbDriver {
+        KbDriver {
+            key_map: KeyMap::init(),
+            key_state: KeyState::init(),
+            leds: Leds::NONE,
+            caps_lock: CapsLockSync::new(),
+            rolled_over: false,
+            released: true,
+        }
+    }
+
+    fn process_key_event(
+        &mut self,
+        key_scan: KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>,
+    ) -> Option<Vec<KbOracleReports>> {
+        let mut key_state = KeyState::init();
+        let ghosted = key_scan.ghosted();
+
+        let (modifiers, characters) = key_scan.into_decoder();
+        let modifier_scan_codes: Vec<u8> = modifiers.into();
+        let character_scan_codes: Vec<ScanCode> = Into::<Vec<u8>>::into(characters)
+            .into_iter()
+            .map(ScanCode::from)
+            .collect();
+
+        /*
+
+        info!(
+            "{} :: {}",
+            modifier_scan_codes
+                .iter()
+                .map(|k| utils::hex::u8_to_hex_string(*k))
+                .collect::<Vec<String>>()
+                .iter()
+                .map(|h| h.as_str())
+                .collect::<Vec<&str>>()
+                .as_slice(),
+            character_scan_codes
+                .iter()
+                .map(|k| utils::hex::u8_to_hex_string(k.0))
+                .collect::<Vec<String>>()
+                .iter()
+                .map(|h| h.as_str())
+                .collect::<Vec<&str>>()
+                .as_slice()
+        );
+         */
+
+        if !modifier_scan_codes.is_empty() || !character_scan_codes.is_empty() {
+            let chord = key_state
+                .handle_modifier_event(modifier_scan_codes.iter().map(|&m| m.into()).collect());
+            let layer: LayerMask = self.key_map.resolve_layer(chord);
+
+            let modified: Modify = {
+                let scan_code = ScanCode::NONE;
+                let key_event_input = self.key_map.find_input(layer, scan_code);
+                let (handled, _modified_active_keys) =
+                    key_state.handle_key_event(layer, key_event_input.clone());
+                if !self.key_state.previously_pressed(layer, scan_code) {
+                    if !handled {
+                        Modify::None
+                    } else {
+                        if let Some(event) = key_event_input.clone() {
+                            Modify::Record(event)
+                        } else {
+                            Modify::None
+                        }
+                    }
+                } else {
+                    if let Some(event) = key_event_input {
+                        Modify::Remove(event)
+                    } else {
+                        Modify::None
+                    }
+                }
+            };
+            /*
+             */
+
+            if character_scan_codes.is_empty() {
+                match &modified {
+                    Modify::Record(modify_event) => {
+                        let dropping = self.key_state.oracle.temporal_logs.iter().enumerate().fold(
+                            Vec::new(),
+                            |mut acc, (idx, l)| {
+                                if l.1 .0 == modify_event.layer {
+                                    acc.push((idx, l.1 .0));
+                                }
+                                acc
+                            },
+                        );
+                        self.key_state
+                            .oracle
+                            .drop_indexes(dropping.iter().map(|&m| m.0).collect());
+
+                        let a = modify_event.clone();
+                        self.key_state.record_key_event(
+                            a.layer,
+                            a.scan_code,
+                            // @CHECK::: does this finally overwrite bare
+                            // and render the keyboard vec from our declared hid map?
+                            a,
+                        );
+                    }
+                    Modify::Remove(modify_event) => {
+                        let a = modify_event.clone();
+                        self.key_state.remove_key_event(a.layer, a.scan_code, a);
+                    }
+                    Modify::None => {}
+                }
+            }
+            for scan_code in character_scan_codes.clone() {
+                let mut character_layer = layer;
+                let mut key_event_input = self.key_map.find_input(character_layer, scan_code);
+
+                if key_event_input.is_none() {
+                    character_layer = LayerMask::BARE;
+                    key_event_input = self.key_map.find_input(character_layer, scan_code);
+                }
+
+                let (handled, handled_modified) =
+                    key_state.handle_key_event(character_layer, key_event_input.clone());
+
+                match key_event_input.clone() {
+                    Some(event_input) => match &modified {
+                        Modify::Record(modify_event) => {
+                            if !layer.is_bare() && !event_input.scan_code.is_none() {
+                                let a = modify_event.clone();
+                                if handled_modified.is_some() {
+                                    let temporal_log = (a.layer, a.scan_code, a.usb_hid);
+                                    self.key_state.oracle.remove(a, temporal_log);
+                                } else {
+                                    self.key_state.record_key_event(
+                                        a.layer,
+                                        a.scan_code,
+                                        // @CHECK::: does this finally overwrite bare
+                                        // and render the keyboard vec from our declared hid map?
+                                        a,
+                                    );
+                                }
+                                /*
+                                self.key_state.remove_key_event(
+                                    a.layer,
+                                    a.scan_code,
+                                    // @CHECK::: does this finally overwrite bare modifiers
+                                    // and render the keyboard vec from our declared hid map?
+                                    a,
+                                );
+                                */
+                            } else if !layer.is_bare() && event_input.scan_code.is_none() {
+                                // need to garbage collect a former modifier with key code
+                                if handled_modified.is_none() {
+                                    let a = modify_event.clone();
+                                    self.key_state.record_key_event(
+                                        a.layer,
+                                        a.scan_code,
+                                        // @CHECK::: does this finally overwrite bare modifiers
+                                        // and render the keyboard vec from our declared hid map?
+                                        a,
+                                    );
+                                }
+                            }
+                        }
+                        Modify::Remove(modify_event) => {
+                            let a = modify_event.clone();
+                            self.key_state.remove_key_event(a.layer, a.scan_code, a);
+                        }
+                        Modify::None => {}
+                    },
+                    None => {}
+                }
+
+                if !self
+                    .key_state
+                    .previously_pressed(character_layer, scan_code)
+                {
+                    if handled {
+                        if let Some(event) = key_event_input {
+                            self.key_state
+                                .record_key_event(character_layer, scan_code, event)
+                        }
+                    }
+                } else {
+                    if let Some(event) = key_event_input {
+                        self.key_state
+                            .remove_key_event(character_layer, scan_code, event)
+                    }
+                }
+            }
+
+            let reports = self.key_state.generate_reports((
+                modifier_scan_codes
+                    .iter()
+                    .map(|&m| LayerMask::from(m))
+                    .chain([layer])
+                    .collect(),
+                character_scan_codes,
+            ));
+            self.released = false;
+            Some(self.roll_over(reports, ghosted))
+        } else if self.released {
+            // the host already has the release, repeating it at the host's
+            // idle rate is up to the firmware rather than every scan.
+            Some(self.roll_over(Vec::new(), ghosted))
+        } else {
+            let reports = self.key_state.release();
+            self.released = true;
+            Some(self.roll_over(reports, ghosted))
+        }
+    }
+
+    fn hid_report(self) -> Vec<KeyboardReport> {
+        let mut reports: Vec<KeyboardReport> = Vec::new();
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kb::decoder::ScanEvent;
+
+    /// the keyboard reports of a scan holding the keys of `scan_codes`.
+    fn keyboard_reports(driver: &mut KbDriver, scan_codes: &[u8]) -> Vec<NkroReport> {
+        let mut key_scan = KeyScan::released();
+        for &scan_code in scan_codes {
+            key_scan.apply(ScanEvent::Key {
+                scan_code,
+                pressed: true,
+            });
+        }
+        driver
+            .process_key_event(key_scan)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|report| match report {
+                KbOracleReports::Keyboard(keyboard) => Some(keyboard),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_the_release_once() {
+        let mut driver = KbDriver::init();
+        // nothing was held since boot.
+        assert!(keyboard_reports(&mut driver, &[]).is_empty());
+
+        // the matrix's `1` key.
+        let held = keyboard_reports(&mut driver, &[0x01]);
+        assert!(held.len() == 1 && held[0].keycodes().eq([0x1E]));
+
+        let released = keyboard_reports(&mut driver, &[]);
+        assert!(released.len() == 1 && released[0].is_empty());
+        for _ in 0..3 {
+            assert!(keyboard_reports(&mut driver, &[]).is_empty());
+        }
+
+        // and again after the next key.
+        assert!(keyboard_reports(&mut driver, &[0x01]).len() == 1);
+        assert!(keyboard_reports(&mut driver, &[]).len() == 1);
+        assert!(keyboard_reports(&mut driver, &[]).is_empty());
+    }
+}
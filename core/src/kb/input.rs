@@ -0,0 +1,144 @@
+use crate::{shared::kb::KeyEvent, utils};
+use alloc::string::*;
+use core::ops::{BitOr, BitOrAssign};
+
+// pub const MOD_FN: u8 = 0x80u8;
+pub const KEY_ASCII: u8 = 0x7Fu8;
+
+/// the bare modifier entry of a layer to be recorded or removed alongside the
+/// characters of a scan.
+#[derive(Clone)]
+pub enum Modify {
+    Record(KeyEvent),
+    Remove(KeyEvent),
+    None,
+}
+
+/// the set of modifier switches held during a scan. every modifier is a single
+/// bit so any of the 32 chords is addressable, and the chord doubles as the
+/// `LayerMask` it resolves on.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const BARE: Modifiers = Modifiers(0x00);
+
+    pub const CONTROL: Modifiers = Modifiers(0x01); // Control
+    pub const RESET: Modifiers = Modifiers(0x02); // Reset
+    pub const SHIFT: Modifiers = Modifiers(0x04); // Shift
+    pub const OPEN_APPLE: Modifiers = Modifiers(0x40); // SW0
+    pub const CLOSED_APPLE: Modifiers = Modifiers(0x80); // SW1
+
+    pub const OPEN_CLOSED_APPLE: Modifiers = Modifiers(0x40 | 0x80); // SW0 + SW1
+
+    pub const ALL: Modifiers = Modifiers(0x01 | 0x02 | 0x04 | 0x40 | 0x80);
+
+    /// the order modifiers are let go of when a chord has no layer of its own,
+    /// see `Modifiers::fallback`. reset is inaccessible as a layer so it goes
+    /// first, the apples are what most layers are built on so they go last.
+    pub const FALLBACK_ORDER: [Modifiers; 5] = [
+        Self::RESET,
+        Self::SHIFT,
+        Self::CONTROL,
+        Self::CLOSED_APPLE,
+        Self::OPEN_APPLE,
+    ];
+
+    /// a chord from its bits, `None` if any bit is not a modifier.
+    pub const fn get(bits: u8) -> Option<Modifiers> {
+        if bits & !Self::ALL.0 == 0 {
+            Some(Modifiers(bits))
+        } else {
+            None
+        }
+    }
+
+    /// a chord from its bits, dropping any bit that is not a modifier.
+    pub const fn from_bits_truncate(bits: u8) -> Modifiers {
+        Modifiers(bits & Self::ALL.0)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn is_bare(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Modifiers) {
+        self.0 |= other.0
+    }
+
+    pub fn remove(&mut self, other: Modifiers) {
+        self.0 &= !other.0
+    }
+
+    /// every chord in ascending order, bare first.
+    pub fn chords() -> impl Iterator<Item = Modifiers> {
+        (0..=u8::MAX).filter_map(Self::get)
+    }
+
+    /// the next chord to try when this one has no layer, i.e. this chord less
+    /// the first held modifier in `FALLBACK_ORDER`. `None` once bare.
+    pub fn fallback(self) -> Option<Modifiers> {
+        Self::FALLBACK_ORDER
+            .iter()
+            .find(|m| self.contains(**m))
+            .map(|m| {
+                let mut next = self;
+                next.remove(*m);
+                next
+            })
+    }
+
+    pub fn outer_as_string(self) -> String {
+        match self {
+            Self::BARE => "bare".to_string(),
+            Self::OPEN_APPLE => "open".to_string(),
+            Self::CLOSED_APPLE => "closed".to_string(),
+            Self::CONTROL => "control".to_string(),
+            Self::RESET => "reset".to_string(),
+            Self::SHIFT => "shift".to_string(),
+            _ => "".to_string(),
+        }
+    }
+
+    pub fn is_multiple(self) -> bool {
+        self.0.count_ones() > 1
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs)
+    }
+}
+
+impl From<Modifiers> for u8 {
+    fn from(value: Modifiers) -> u8 {
+        value.0
+    }
+}
+
+impl From<u8> for Modifiers {
+    fn from(value: u8) -> Modifiers {
+        info!(
+            "modifier from {}",
+            utils::hex::u8_to_hex_string(value).as_str()
+        );
+        Modifiers::from_bits_truncate(value)
+    }
+}
@@ -0,0 +1,93 @@
+use super::{KeyMapEntry, KeyMapLayer, KeyMapTable};
+use crate::kb::page::{Consumer, Keyboard};
+use crate::shared::kb::LayerMask;
+
+#[derive(Clone)]
+pub enum KeyboardMapEntrant {
+    Keyboard(Keyboard),
+    Consumer(Consumer),
+    System(System),
+}
+
+/// the system control usages of the generic desktop page (0x01).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum System {
+    PowerDown = 0x81,
+    Sleep = 0x82,
+    WakeUp = 0x83,
+}
+
+impl System {
+    pub fn get(usage: u16) -> Option<System> {
+        match usage {
+            0x81 => Some(System::PowerDown),
+            0x82 => Some(System::Sleep),
+            0x83 => Some(System::WakeUp),
+            _ => None,
+        }
+    }
+}
+
+impl From<System> for u8 {
+    fn from(system: System) -> u8 {
+        system as u8
+    }
+}
+
+impl PartialEq for KeyboardMapEntrant {
+    fn eq(&self, other: &Self) -> bool {
+        match self {
+            KeyboardMapEntrant::Keyboard(keyboard) => match other {
+                KeyboardMapEntrant::Keyboard(other_keyboard) => {
+                    let self_u8: u8 = keyboard.clone().into();
+                    self_u8 == other_keyboard.clone().into()
+                }
+                _ => false,
+            },
+            KeyboardMapEntrant::Consumer(consumer) => match other {
+                KeyboardMapEntrant::Consumer(other_consumer) => {
+                    let self_u16: u16 = consumer.clone().into();
+                    self_u16 == other_consumer.clone().into()
+                }
+                _ => false,
+            },
+            KeyboardMapEntrant::System(system) => match other {
+                KeyboardMapEntrant::System(other_system) => system == other_system,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Into<u8> for KeyboardMapEntrant {
+    fn into(self) -> u8 {
+        match self {
+            KeyboardMapEntrant::Keyboard(keyboard) => {
+                let keyboard_u8: u8 = keyboard.into();
+                keyboard_u8
+            }
+            KeyboardMapEntrant::Consumer(consumer) => {
+                let consumer_u16: u16 = consumer.into();
+                consumer_u16 as u8
+            }
+            KeyboardMapEntrant::System(system) => system.into(),
+        }
+    }
+}
+
+impl Into<u16> for KeyboardMapEntrant {
+    fn into(self) -> u16 {
+        match self {
+            KeyboardMapEntrant::Keyboard(keyboard) => 0,
+            KeyboardMapEntrant::Consumer(consumer) => consumer.into(),
+            KeyboardMapEntrant::System(system) => system as u16,
+        }
+    }
+}
+
+// `HID_KEYBOARD_LAYERS`, generated by `build.rs` from `keymap.toml`.
+include!(concat!(env!("OUT_DIR"), "/keymap.rs"));
+
+/// the built-in keymap, resolved into a flash resident lookup table at compile time.
+pub static HID_KEYBOARD_MAP: KeyMapTable = KeyMapTable::new(&HID_KEYBOARD_LAYERS);
@@ -0,0 +1,151 @@
+mod hid;
+pub mod storage;
+
+use crate::kb::input::Modifiers;
+use crate::shared::kb::{KeyAction, KeyEvent, KeyboardKeyMap, LayerMask, ScanCode};
+use crate::utils::hex::u8_to_hex_string;
+
+pub use hid::{KeyboardMapEntrant, System};
+
+/// scan codes top out at 0x79 so every layer fits in 128 slots.
+pub const LAYOUT_KEYS: usize = 128;
+
+/// a single keymap slot, laid out so the whole table can live in flash.
+#[derive(Clone, Copy)]
+pub struct KeyMapEntry {
+    pub key_up: ScanCode,
+    pub key_down: ScanCode,
+    pub usb_hid: &'static [KeyboardMapEntrant],
+}
+
+impl KeyMapEntry {
+    pub const fn new(key_up: u8, key_down: u8, usb_hid: &'static [KeyboardMapEntrant]) -> Self {
+        Self {
+            key_up: ScanCode(key_up),
+            key_down: ScanCode(key_down),
+            usb_hid,
+        }
+    }
+}
+
+/// every key of a modifier chord, indexed directly by ascii scan code.
+pub struct KeyMapLayer {
+    pub mask: LayerMask,
+    pub keys: [Option<KeyMapEntry>; LAYOUT_KEYS],
+}
+
+impl KeyMapLayer {
+    /// scatter `(scan code, entry)` pairs into their slots. a scan code
+    /// listed twice keeps the later entry.
+    pub const fn new(mask: LayerMask, entries: &[(u8, KeyMapEntry)]) -> Self {
+        let mut keys: [Option<KeyMapEntry>; LAYOUT_KEYS] = [None; LAYOUT_KEYS];
+
+        let mut i = 0;
+        while i < entries.len() {
+            let (scan_code, entry) = entries[i];
+            keys[(scan_code & 0x7F) as usize] = Some(entry);
+            i += 1;
+        }
+
+        Self { mask, keys }
+    }
+}
+
+/// the layers of a keymap plus a mask -> layer index so that a lookup is
+/// two array indexes and nothing else.
+pub struct KeyMapTable {
+    pub layers: &'static [KeyMapLayer],
+    index: [Option<u8>; 256],
+}
+
+impl KeyMapTable {
+    pub const fn new(layers: &'static [KeyMapLayer]) -> Self {
+        let mut index: [Option<u8>; 256] = [None; 256];
+
+        let mut i = 0;
+        while i < layers.len() {
+            index[layers[i].mask.0 as usize] = Some(i as u8);
+            i += 1;
+        }
+
+        Self { layers, index }
+    }
+
+    pub fn layer(&self, layer: LayerMask) -> Option<&KeyMapLayer> {
+        self.index[layer.0 as usize].map(|i| &self.layers[i as usize])
+    }
+
+    pub fn entry(&self, layer: LayerMask, scan_code: ScanCode) -> Option<&KeyMapEntry> {
+        self.layer(layer)?.keys[scan_code.ascii().0 as usize].as_ref()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct KeyMap {
+    pub table: &'static KeyMapTable,
+}
+
+impl KeyMap {
+    /// the built-in keymap compiled from `keymap.toml`.
+    pub fn init() -> KeyMap {
+        Self {
+            table: &hid::HID_KEYBOARD_MAP,
+        }
+    }
+
+    /// the keymap serialized in `stored`, falling back to the built-in one when
+    /// the region is empty or corrupt.
+    pub fn load(stored: &[u8]) -> KeyMap {
+        match storage::decode(stored) {
+            Ok(table) => {
+                info!("loaded the stored keymap, {} layers", table.layers.len());
+                Self { table }
+            }
+            Err(err) => {
+                error!("falling back to the built-in keymap: {}", err);
+                Self::init()
+            }
+        }
+    }
+}
+
+impl KeyboardKeyMap for KeyMap {
+    fn find_input(&self, layer: LayerMask, scan_code: ScanCode) -> Option<KeyEvent> {
+        match self.table.entry(layer, scan_code) {
+            Some(entry) => Some(KeyEvent {
+                action: if scan_code.is_key_down() {
+                    KeyAction::Press
+                } else {
+                    KeyAction::Release
+                },
+                layer,
+                scan_code: entry.key_down,
+                usb_hid: entry.usb_hid,
+            }),
+            None => {
+                error!(
+                    "UNABLE TO FIND layout for {} input for {} :::::",
+                    u8_to_hex_string(layer.0).as_str(),
+                    u8_to_hex_string(scan_code.0).as_str(),
+                );
+                None
+            }
+        }
+    }
+
+    fn resolve_layer(&self, modifiers: Modifiers) -> LayerMask {
+        let mut chord = Some(modifiers);
+        while let Some(modifiers) = chord {
+            let layer = LayerMask::from(modifiers);
+            if self.table.layer(layer).is_some() {
+                return layer;
+            }
+            chord = modifiers.fallback();
+        }
+        error!(
+            "UNABLE TO RESOLVE a layer for {} :::::",
+            u8_to_hex_string(modifiers.bits()).as_str(),
+        );
+        LayerMask::BARE
+    }
+}
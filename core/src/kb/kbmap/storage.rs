@@ -0,0 +1,392 @@
+//! the serialized form of a keymap, as kept in the flash region after the
+//! program image (see `memory.x`) and swapped without reflashing firmware.
+//!
+//! all integers are little endian.
+//!
+//! | bytes | header                                  |
+//! |-------|-----------------------------------------|
+//! | 4     | `KEYMAP_MAGIC`                          |
+//! | 1     | `KEYMAP_VERSION`                        |
+//! | 1     | layer count                             |
+//! | 2     | reserved, zero                          |
+//! | 4     | payload length                          |
+//! | 4     | crc-32 of the payload                   |
+//!
+//! the payload is every layer as `mask, key count` followed by its keys as
+//! `scan code, key up, key down, usage count` and then the usages as
+//! `usage page, usage id (u16)`.
+//!
+//! a key holds at most one consumer and one system control usage, their
+//! reports have room for a single usage each (see `fits_reports`). a keymap
+//! with a key mapping more is malformed.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use super::{KeyMapEntry, KeyMapLayer, KeyMapTable, KeyboardMapEntrant, System, LAYOUT_KEYS};
+use crate::kb::input::Modifiers;
+use crate::kb::page::{Consumer, Keyboard};
+use crate::shared::kb::LayerMask;
+use crate::utils::crc::crc32;
+
+pub const KEYMAP_MAGIC: [u8; 4] = *b"A2KM";
+/// bumped whenever the layout above changes so an older blob isn't misread.
+pub const KEYMAP_VERSION: u8 = 1;
+pub const KEYMAP_HEADER_LEN: usize = 16;
+
+// the usb hid usage pages of `KeyboardMapEntrant`.
+pub const PAGE_DESKTOP: u8 = 0x01;
+pub const PAGE_KEYBOARD: u8 = 0x07;
+pub const PAGE_CONSUMER: u8 = 0x0C;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum KeyMapBlobError {
+    /// erased flash, no keymap was ever stored.
+    Empty,
+    /// stored by a firmware with a different blob layout.
+    Version(u8),
+    /// the header claims more bytes than there are.
+    Truncated,
+    /// the payload doesn't match its crc.
+    Crc,
+    /// the payload checks out but doesn't describe a keymap.
+    Malformed,
+}
+
+/// a key as stored: `(scan code, key up, key down, usages)`.
+pub type StoredKey = (u8, u8, u8, Vec<KeyboardMapEntrant>);
+/// a layer as stored, keys in ascending scan code order.
+pub type StoredLayer = (LayerMask, Vec<StoredKey>);
+
+/// the layers of `table` in their stored, editable form.
+pub fn layers_of(table: &KeyMapTable) -> Vec<StoredLayer> {
+    table
+        .layers
+        .iter()
+        .map(|layer| {
+            let keys = layer
+                .keys
+                .iter()
+                .enumerate()
+                .filter_map(|(scan_code, entry)| {
+                    entry.map(|entry| {
+                        (
+                            scan_code as u8,
+                            entry.key_up.0,
+                            entry.key_down.0,
+                            entry.usb_hid.to_vec(),
+                        )
+                    })
+                })
+                .collect::<Vec<StoredKey>>();
+            (layer.mask, keys)
+        })
+        .collect()
+}
+
+/// serialize `table` into a blob `decode` accepts.
+pub fn encode(table: &KeyMapTable) -> Vec<u8> {
+    encode_layers(&layers_of(table))
+}
+
+/// serialize `layers` into a blob `decode` accepts.
+pub fn encode_layers(layers: &[StoredLayer]) -> Vec<u8> {
+    let mut payload = Vec::new();
+
+    for (mask, keys) in layers.iter() {
+        payload.push(mask.0);
+        payload.push(keys.len() as u8);
+
+        for (scan_code, key_up, key_down, usb_hid) in keys.iter() {
+            payload.extend_from_slice(&[*scan_code, *key_up, *key_down, usb_hid.len() as u8]);
+            for entrant in usb_hid.iter() {
+                let (page, usage) = usage_of(entrant);
+                payload.push(page);
+                payload.extend_from_slice(&usage.to_le_bytes());
+            }
+        }
+    }
+
+    let mut blob = Vec::with_capacity(KEYMAP_HEADER_LEN + payload.len());
+    blob.extend_from_slice(&KEYMAP_MAGIC);
+    blob.push(KEYMAP_VERSION);
+    blob.push(layers.len() as u8);
+    blob.extend_from_slice(&[0x00, 0x00]);
+    blob.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    blob.extend_from_slice(&crc32(&payload).to_le_bytes());
+    blob.extend_from_slice(&payload);
+    blob
+}
+
+/// the `(usage page, usage id)` an entrant is stored as.
+pub fn usage_of(entrant: &KeyboardMapEntrant) -> (u8, u16) {
+    match entrant.clone() {
+        KeyboardMapEntrant::Keyboard(keyboard) => {
+            let usage: u8 = keyboard.into();
+            (PAGE_KEYBOARD, usage as u16)
+        }
+        KeyboardMapEntrant::Consumer(consumer) => (PAGE_CONSUMER, consumer.into()),
+        KeyboardMapEntrant::System(system) => (PAGE_DESKTOP, system as u16),
+    }
+}
+
+/// the entrant for a stored `(usage page, usage id)`, `None` for an unknown
+/// page or a generic desktop usage other than a system control.
+pub fn entrant_of(page: u8, usage: u16) -> Option<KeyboardMapEntrant> {
+    match page {
+        PAGE_DESKTOP => System::get(usage).map(KeyboardMapEntrant::System),
+        PAGE_KEYBOARD => Some(KeyboardMapEntrant::Keyboard(Keyboard::from(usage as u8))),
+        PAGE_CONSUMER => Some(KeyboardMapEntrant::Consumer(Consumer::from(usage))),
+        _ => None,
+    }
+}
+
+/// whether the consumer and system control reports can carry every usage of
+/// a key, that is at most one of each.
+pub fn fits_reports(usb_hid: &[KeyboardMapEntrant]) -> bool {
+    let consumer = usb_hid
+        .iter()
+        .filter(|entrant| matches!(entrant, KeyboardMapEntrant::Consumer(_)))
+        .count();
+    let system = usb_hid
+        .iter()
+        .filter(|entrant| matches!(entrant, KeyboardMapEntrant::System(_)))
+        .count();
+    consumer <= 1 && system <= 1
+}
+
+/// check and deserialize a blob into a table.
+///
+/// the table is leaked to satisfy the `&'static` the lookup is built on, so
+/// this is meant to run once per stored keymap rather than per scan.
+pub fn decode(blob: &[u8]) -> Result<&'static KeyMapTable, KeyMapBlobError> {
+    let layers = decode_layers(blob)?
+        .into_iter()
+        .map(|(mask, keys)| {
+            let entries = keys
+                .into_iter()
+                .map(|(scan_code, key_up, key_down, usb_hid)| {
+                    let usb_hid: &'static [KeyboardMapEntrant] =
+                        Box::leak(usb_hid.into_boxed_slice());
+                    (scan_code, KeyMapEntry::new(key_up, key_down, usb_hid))
+                })
+                .collect::<Vec<(u8, KeyMapEntry)>>();
+            KeyMapLayer::new(mask, &entries)
+        })
+        .collect::<Vec<KeyMapLayer>>();
+
+    let layers: &'static [KeyMapLayer] = Box::leak(layers.into_boxed_slice());
+    Ok(Box::leak(Box::new(KeyMapTable::new(layers))))
+}
+
+/// check and deserialize a blob into its stored, editable form.
+pub fn decode_layers(blob: &[u8]) -> Result<Vec<StoredLayer>, KeyMapBlobError> {
+    let payload = verify(blob)?;
+    parse(blob[5], payload).ok_or(KeyMapBlobError::Malformed)
+}
+
+/// the payload of `blob` once its header and crc check out.
+fn verify(blob: &[u8]) -> Result<&[u8], KeyMapBlobError> {
+    if blob.len() < KEYMAP_HEADER_LEN || blob[0..4] != KEYMAP_MAGIC {
+        return Err(KeyMapBlobError::Empty);
+    }
+    if blob[4] != KEYMAP_VERSION {
+        return Err(KeyMapBlobError::Version(blob[4]));
+    }
+
+    let len = u32::from_le_bytes([blob[8], blob[9], blob[10], blob[11]]) as usize;
+    let crc = u32::from_le_bytes([blob[12], blob[13], blob[14], blob[15]]);
+
+    let payload = blob
+        .get(KEYMAP_HEADER_LEN..KEYMAP_HEADER_LEN + len)
+        .ok_or(KeyMapBlobError::Truncated)?;
+    if crc32(payload) != crc {
+        return Err(KeyMapBlobError::Crc);
+    }
+
+    Ok(payload)
+}
+
+fn parse(layer_count: u8, payload: &[u8]) -> Option<Vec<StoredLayer>> {
+    let mut bytes = payload.iter().copied();
+    let mut layers: Vec<StoredLayer> = Vec::new();
+
+    for _ in 0..layer_count {
+        let mask = LayerMask::from(Modifiers::get(bytes.next()?)?);
+        if layers.iter().any(|(layer, _)| *layer == mask) {
+            return None;
+        }
+
+        let mut keys = Vec::new();
+        for _ in 0..bytes.next()? {
+            let scan_code = bytes.next()?;
+            if scan_code as usize >= LAYOUT_KEYS {
+                return None;
+            }
+            let key_up = bytes.next()?;
+            let key_down = bytes.next()?;
+
+            let mut usb_hid = Vec::new();
+            for _ in 0..bytes.next()? {
+                let page = bytes.next()?;
+                let usage = u16::from_le_bytes([bytes.next()?, bytes.next()?]);
+                usb_hid.push(entrant_of(page, usage)?);
+            }
+            if !fits_reports(&usb_hid) {
+                return None;
+            }
+
+            keys.push((scan_code, key_up, key_down, usb_hid));
+        }
+
+        layers.push((mask, keys));
+    }
+
+    // anything after the last layer means the count and payload disagree.
+    match bytes.next() {
+        Some(_) => None,
+        None => Some(layers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kb::kbmap::KeyMap;
+    use crate::shared::kb::ScanCode;
+    use alloc::vec;
+
+    fn layers() -> Vec<StoredLayer> {
+        vec![
+            (
+                LayerMask(0x00),
+                vec![
+                    (
+                        0x41,
+                        0x41,
+                        0xC1,
+                        vec![KeyboardMapEntrant::Keyboard(Keyboard::A)],
+                    ),
+                    (
+                        0x61,
+                        0x61,
+                        0xE1,
+                        vec![
+                            KeyboardMapEntrant::Keyboard(Keyboard::LeftShift),
+                            KeyboardMapEntrant::Keyboard(Keyboard::A),
+                        ],
+                    ),
+                ],
+            ),
+            (
+                LayerMask(0x40),
+                vec![(
+                    0x31,
+                    0x31,
+                    0xB1,
+                    vec![
+                        KeyboardMapEntrant::Consumer(Consumer::Mute),
+                        KeyboardMapEntrant::System(System::Sleep),
+                    ],
+                )],
+            ),
+        ]
+    }
+
+    /// `blob` with its header length and crc rewritten to match `payload`.
+    fn with_payload(blob: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut blob = blob[..KEYMAP_HEADER_LEN].to_vec();
+        blob[8..12].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+        blob[12..16].copy_from_slice(&crc32(payload).to_le_bytes());
+        blob.extend_from_slice(payload);
+        blob
+    }
+
+    #[test]
+    fn round_trip() {
+        let blob = encode_layers(&layers());
+        assert!(decode_layers(&blob) == Ok(layers()));
+
+        let table = decode(&blob).ok().unwrap();
+        let entry = table.entry(LayerMask(0x40), ScanCode(0xB1)).unwrap();
+        assert!(entry.key_down == ScanCode(0xB1));
+        assert!(
+            entry.usb_hid
+                == [
+                    KeyboardMapEntrant::Consumer(Consumer::Mute),
+                    KeyboardMapEntrant::System(System::Sleep)
+                ]
+        );
+        assert!(encode(table) == blob);
+    }
+
+    #[test]
+    fn bad_magic_is_empty() {
+        let mut blob = encode_layers(&layers());
+        blob[0] = b'B';
+        assert!(decode_layers(&blob) == Err(KeyMapBlobError::Empty));
+
+        // erased flash and anything shorter than a header.
+        assert!(decode_layers(&[0xFF; 64]) == Err(KeyMapBlobError::Empty));
+        assert!(decode_layers(&blob[..KEYMAP_HEADER_LEN - 1]) == Err(KeyMapBlobError::Empty));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut blob = encode_layers(&layers());
+        blob[4] = KEYMAP_VERSION + 1;
+        assert!(decode_layers(&blob) == Err(KeyMapBlobError::Version(KEYMAP_VERSION + 1)));
+    }
+
+    #[test]
+    fn truncated_payload() {
+        let blob = encode_layers(&layers());
+        assert!(decode_layers(&blob[..blob.len() - 1]) == Err(KeyMapBlobError::Truncated));
+        assert!(decode_layers(&blob[..KEYMAP_HEADER_LEN]) == Err(KeyMapBlobError::Truncated));
+    }
+
+    #[test]
+    fn payload_length_mismatch_is_malformed() {
+        let blob = encode_layers(&layers());
+        let payload = &blob[KEYMAP_HEADER_LEN..];
+
+        // a byte past the last layer.
+        let mut longer = payload.to_vec();
+        longer.push(0x00);
+        assert!(decode_layers(&with_payload(&blob, &longer)) == Err(KeyMapBlobError::Malformed));
+
+        // the last usage cut short.
+        let shorter = &payload[..payload.len() - 1];
+        assert!(decode_layers(&with_payload(&blob, shorter)) == Err(KeyMapBlobError::Malformed));
+
+        // a layer more than the payload holds.
+        let mut blob = blob.clone();
+        blob[5] += 1;
+        assert!(decode_layers(&blob) == Err(KeyMapBlobError::Malformed));
+    }
+
+    #[test]
+    fn crc_mismatch() {
+        let mut blob = encode_layers(&layers());
+        let last = blob.len() - 1;
+        blob[last] ^= 0x01;
+        assert!(decode_layers(&blob) == Err(KeyMapBlobError::Crc));
+    }
+
+    #[test]
+    fn load_falls_back_to_init() {
+        let builtin = KeyMap::init().table;
+
+        let mut blob = encode_layers(&layers());
+        let last = blob.len() - 1;
+        blob[last] ^= 0x01;
+        assert!(core::ptr::eq(KeyMap::load(&blob).table, builtin));
+        assert!(core::ptr::eq(KeyMap::load(&[0xFF; 64]).table, builtin));
+
+        let blob = encode_layers(&layers());
+        let stored = KeyMap::load(&blob).table;
+        assert!(!core::ptr::eq(stored, builtin));
+        assert!(stored.layers.len() == 2);
+    }
+}
@@ -0,0 +1,143 @@
+//! the lock leds the host sets with the keyboard's output report, and keeping
+//! the IIe's latching CAPS LOCK in step with them.
+//!
+//! the latch holds its own state so tapping caps lock on every change of it
+//! goes wrong the moment the host's caps lock is toggled from elsewhere (an
+//! other keyboard, a kvm, a reboot). instead a change of the latch only taps
+//! caps lock while the host's led disagrees with it.
+
+use super::oracle::KbOracleReports;
+use super::page::Keyboard;
+use super::report::NkroReport;
+
+/// how long to wait on the host to answer a tap with its leds before tapping
+/// again. timed by the caller's clock, the scan rate is a setting.
+const CAPS_LOCK_SETTLE_MS: u32 = 500;
+/// taps to try before giving up on a host that never reports its leds.
+const CAPS_LOCK_ATTEMPTS: u8 = 3;
+
+/// the led bits of a keyboard output report.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Leds(u8);
+
+impl Leds {
+    pub const NONE: Leds = Leds(0x00);
+
+    pub const NUM_LOCK: Leds = Leds(0x01);
+    pub const CAPS_LOCK: Leds = Leds(0x02);
+    pub const SCROLL_LOCK: Leds = Leds(0x04);
+    pub const COMPOSE: Leds = Leds(0x08);
+    pub const KANA: Leds = Leds(0x10);
+
+    /// the leds of an output report, dropping the padding bits.
+    pub const fn from_report(byte: u8) -> Leds {
+        Leds(byte & 0x1F)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Leds) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct CapsLockSync {
+    /// the latch as of the last scan, `None` before the first.
+    latched: Option<bool>,
+    /// the caps lock the host is being brought to, `None` once it agrees.
+    target: Option<bool>,
+    /// when caps lock was last tapped, `None` until the first tap.
+    tapped_at: Option<u32>,
+    attempts: u8,
+}
+
+impl CapsLockSync {
+    pub const fn new() -> Self {
+        Self {
+            latched: None,
+            target: None,
+            tapped_at: None,
+            attempts: 0,
+        }
+    }
+
+    /// the reports tapping caps lock when the latch has moved and the host's
+    /// caps lock doesn't match it yet, called once per scan at `now_ms`.
+    pub fn sync(&mut self, latched: bool, leds: Leds, now_ms: u32) -> Option<[KbOracleReports; 2]> {
+        if self.latched != Some(latched) {
+            self.latched = Some(latched);
+            self.target = Some(latched);
+            self.tapped_at = None;
+            self.attempts = 0;
+        }
+
+        let target = self.target?;
+        if leds.contains(Leds::CAPS_LOCK) == target || self.attempts >= CAPS_LOCK_ATTEMPTS {
+            self.target = None;
+            return None;
+        }
+        let settling = self
+            .tapped_at
+            .is_some_and(|tapped_at| now_ms.wrapping_sub(tapped_at) < CAPS_LOCK_SETTLE_MS);
+        if settling {
+            return None;
+        }
+
+        self.tapped_at = Some(now_ms);
+        self.attempts += 1;
+        Some([
+            KbOracleReports::Keyboard(NkroReport::from_usages(
+                [Keyboard::CapsLock.into()].into_iter(),
+            )),
+            KbOracleReports::Keyboard(NkroReport::empty()),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taps_until_the_host_follows() {
+        let mut sync = CapsLockSync::new();
+        assert!(sync.sync(true, Leds::NONE, 0).is_some());
+
+        // the host gets the settle window to answer, however many scans it spans.
+        for now_ms in [1, 5, 50, CAPS_LOCK_SETTLE_MS - 1] {
+            assert!(sync.sync(true, Leds::NONE, now_ms).is_none());
+        }
+        assert!(sync.sync(true, Leds::NONE, CAPS_LOCK_SETTLE_MS).is_some());
+
+        // it answers, nothing more to tap.
+        assert!(sync
+            .sync(true, Leds::CAPS_LOCK, CAPS_LOCK_SETTLE_MS + 1)
+            .is_none());
+        assert!(sync
+            .sync(true, Leds::NONE, 10 * CAPS_LOCK_SETTLE_MS)
+            .is_none());
+    }
+
+    #[test]
+    fn gives_up_on_a_silent_host() {
+        let mut sync = CapsLockSync::new();
+        // a tap every settle window, across the wrap of the clock.
+        let taps = (0..10)
+            .filter(|tap| {
+                sync.sync(
+                    true,
+                    Leds::NONE,
+                    (u32::MAX - 100).wrapping_add(tap * CAPS_LOCK_SETTLE_MS),
+                )
+                .is_some()
+            })
+            .count();
+        assert!(taps == CAPS_LOCK_ATTEMPTS as usize);
+
+        // a move of the latch starts over, right away.
+        assert!(sync.sync(false, Leds::CAPS_LOCK, 0).is_some());
+    }
+}
@@ -0,0 +1,9 @@
+pub mod decoder;
+pub mod driver;
+pub mod input;
+pub mod kbmap;
+pub mod leds;
+pub mod oracle;
+pub mod page;
+pub mod report;
+pub mod state;
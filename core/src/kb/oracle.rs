@@ -0,0 +1,430 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::kb::input::Modifiers;
+use crate::kb::kbmap::KeyboardMapEntrant;
+use crate::shared::kb::{KeyEvent, LayerMask, ScanCode};
+
+use super::report::{boot_bytes, NkroReport, BOOT_REPORT_LEN};
+use super::state::ActiveKey;
+
+pub type KbOracleTemporalLog = (LayerMask, KeyEvent, ActiveKey);
+
+pub type KbOracleTicket = usize;
+
+// the report ids of the composite hid descriptor, see `A2PI_DESCRIPTOR`.
+pub const REPORT_ID_KEYBOARD: u8 = 0x01;
+pub const REPORT_ID_CONSUMER: u8 = 0x02;
+pub const REPORT_ID_SYSTEM: u8 = 0x03;
+/// the longest report on the boot keyboard interface, a 6kro report and its id.
+pub const REPORT_MAX_LEN: usize = 1 + BOOT_REPORT_LEN;
+
+/// a consumer control report, `0x0000` when nothing is held.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct MediaKeyboardReport {
+    pub usage_id: u16,
+}
+
+/// a generic desktop system control report, `0x00` when nothing is held.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SystemControlReport {
+    pub usage_id: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KbOracleReports {
+    Keyboard(NkroReport),
+    Consumer(MediaKeyboardReport),
+    System(SystemControlReport),
+}
+
+impl KbOracleReports {
+    pub fn init() -> KbOracleReports {
+        KbOracleReports::Keyboard(NkroReport::empty())
+    }
+    pub fn is_empty(&self) -> bool {
+        match self {
+            KbOracleReports::Keyboard(k) => k.is_empty(),
+            KbOracleReports::Consumer(c) => c.usage_id == 0,
+            KbOracleReports::System(s) => s.usage_id == 0,
+        }
+    }
+
+    /// the report as sent on the boot keyboard interface, prefixed by its
+    /// report id, and its length. the keyboard report is its 6kro form, the
+    /// nkro interface takes `NkroReport::to_bytes` as is.
+    pub fn to_bytes(&self) -> ([u8; REPORT_MAX_LEN], usize) {
+        let mut bytes = [0u8; REPORT_MAX_LEN];
+        let len = match self {
+            KbOracleReports::Keyboard(k) => {
+                bytes[0] = REPORT_ID_KEYBOARD;
+                bytes[1..].copy_from_slice(&boot_bytes(&k.to_boot()));
+                REPORT_MAX_LEN
+            }
+            KbOracleReports::Consumer(c) => {
+                bytes[0] = REPORT_ID_CONSUMER;
+                bytes[1..3].copy_from_slice(&c.usage_id.to_le_bytes());
+                3
+            }
+            KbOracleReports::System(s) => {
+                bytes[0] = REPORT_ID_SYSTEM;
+                bytes[1] = s.usage_id;
+                2
+            }
+        };
+        (bytes, len)
+    }
+}
+
+/*
+  we must omit a former modifier keycode from the `KeyboardReport` as to correctly
+  report macro keys in the next `KeyboardReport` wherein both the modifier and character
+  scan codes have an existant `crate::kb::kbmap::KeyMapEntry`
+  in `self.key_map.table` instanced via [HID_KEYBOARD_MAP](core/src/kb/kbmap/hid.rs).
+
+  observation:
+
+  | initial scan | current scan |
+  |--------------|--------------|
+  | (0x80, 0x00) | (0x80, 0x01) |
+
+  broken psuedo-report:
+
+  | initial report     | current report     |
+  |--------------------|--------------------|
+  | (0x80, 0x00, 0x00) | (0x80, 0x80, 0x01) |
+
+  correct psuedo-report:
+
+  | initial report     | current report     |
+  |--------------------|--------------------|
+  | (0x80, 0x00, 0x00) | (0x80, 0x01, 0x00) |
+
+  ---
+
+  niave me believes we can just always assume this would ever
+  happen where the former modifier key code index is zero.
+  my train of thought was to negate the effect where the bare
+  modifier was being _held_ whilst activating the macro keys.
+
+match &modified {
+    Some(input_event) => {
+        // we can suppose that if
+        if k.0 == layer && &KeyState::of_single_key(k.clone()) == input_event {
+            false
+        } else {
+            true
+        }
+    }
+    None => modifier_scan_codes.clone().contains(&k.0),
+}
+*/
+#[derive(Clone)]
+pub struct KbOracle {
+    // so everytime we mutate `KeyState` this gets called to record
+    // `KeyState.active_keys` - then we index that on the modifier and
+    // character scan codes giving us chronological expression of handled
+    // key presses. this is advantageous since it would tolerate disordered
+    // modifier key press sequences thus correctly rendering the multi-modifier
+    // macro.
+    pub temporal_logs: Vec<(KbOracleTicket, KbOracleTemporalLog)>,
+    pub skipped_tickets: Vec<KbOracleTicket>,
+    pub current_ticket: KbOracleTicket,
+    // the last reported consumer and system control usages, these are
+    // reported independently of the keyboard report.
+    pub consumer_usage: u16,
+    pub system_usage: u8,
+}
+
+impl KbOracle {
+    pub fn init() -> Self {
+        Self {
+            temporal_logs: Vec::new(),
+            skipped_tickets: Vec::new(),
+            current_ticket: 0,
+            consumer_usage: 0,
+            system_usage: 0,
+        }
+    }
+
+    pub fn record(&mut self, key_event: KeyEvent, temporal_log: ActiveKey) {
+        let ticket = self.temporal_logs.len() as KbOracleTicket;
+        let recordable = true;
+
+        let layer = key_event.layer;
+        let is_macro_key = {
+            if key_event.scan_code.0 == layer.0 && !layer.is_bare() && !temporal_log.1.is_none() {
+                true
+            } else {
+                false
+            }
+        };
+
+        let mut idx = 0;
+        let temporal_logs = self.temporal_logs.clone();
+
+        let mut new_cycle = Vec::new();
+        self.temporal_logs.iter().for_each(|active_key| {
+            let (_active_ticket, (active_layer, key, _keys)) = active_key.clone();
+
+            // basically we attest a multi-modifier against all the other key presses
+            // by evaluating for any/all previous key presses of which are of the bare
+            // layer (modifier_code) then hereinstating as skipped when generating
+            // `Vec<KeyboardReport>`.
+
+            if is_macro_key && layer == active_layer && key.scan_code.is_none() {
+                idx += 1;
+                // new_cycle.push(active_key.clone());
+            } else if layer == active_layer {
+                if key_event.same_key(&key) {
+                    // recordable = false;
+                    if idx > 0 {
+                        let last = &temporal_logs.as_slice()[idx - 1];
+                        let (_a, (_b, last_key, _c)) = last.clone();
+                        if !last_key.same_key(&key_event) {
+                            let is_modifier = Modifiers::get(active_layer.0);
+                            if is_modifier.is_some() {
+                                new_cycle.push(active_key.clone());
+                            }
+                        }
+                        idx += 1;
+                        // new_cycle.push(last.clone());
+                    } else {
+                        idx += 1;
+                    }
+                    new_cycle.push(active_key.clone());
+                    // break;
+                } else {
+                    new_cycle.push((ticket, (layer, key_event.clone(), temporal_log.clone())));
+                    new_cycle.push(active_key.clone());
+                    /*
+                     */
+                    idx += 1;
+                }
+            } else {
+                idx += 1;
+            }
+        });
+        if !new_cycle.is_empty() {
+            self.clear();
+            self.temporal_logs = new_cycle;
+            self.temporal_logs = self.temporal_logs.iter().fold(vec![], |mut acc, k| {
+                let (_a, (_b, key, _c)) = k;
+                if acc.iter().any(|x| {
+                    let (_aa, (_bb, acc_key, _cc)) = x;
+                    if acc_key.same_key(key) {
+                        return true;
+                    }
+                    false
+                }) {
+                    acc.push(k.clone());
+                    return acc;
+                }
+                acc
+            });
+            self.temporal_logs.reverse()
+        } else {
+            idx = 0;
+            self.temporal_logs.retain(|active_key| {
+                let (_active_ticket, (active_layer, key, _keys)) = active_key.clone();
+
+                // basically we attest a multi-modifier against all the other key presses
+                // by evaluating for any/all previous key presses of which are of the bare
+                // layer (modifier_code) then hereinstating as skipped when generating
+                // `Vec<KeyboardReport>`.
+
+                if is_macro_key && layer == active_layer && key.scan_code.is_none()
+                {
+                    idx += 1;
+                    false
+                } else if layer == active_layer {
+                    if key_event.same_key(&key) {
+                        // recordable = false;
+                        if idx > 0 {
+                            idx += 1;
+                            false
+                        } else {
+                            idx += 1;
+                            false
+                        }
+                        // break;
+                    } else {
+                        /*
+                         */
+                        idx += 1;
+                        true
+                    }
+                } else {
+                    idx += 1;
+                    true
+                }
+            });
+        }
+        /*
+         */
+
+        if recordable || is_macro_key {
+            // info!("PUSHING TEMP LOG !!!");
+            self.temporal_logs
+                .push((ticket, (layer, key_event, temporal_log)))
+        }
+    }
+
+    pub fn remove(&mut self, key_event: KeyEvent, _temporal_log: ActiveKey) {
+        let layer = key_event.layer;
+        self.temporal_logs.retain(|active_key| {
+            let (active_ticket, (active_layer, key, _keys)) = active_key.clone();
+
+            // basically we attest a multi-modifier against all the other key presses
+            // by evaluating for any/all previous key presses of which are of the bare
+            // layer (modifier_code) then hereinstating as skipped when generating
+            // `Vec<KeyboardReport>`.
+
+            if layer == active_layer && key_event.same_key(&key) {
+                false
+            } else {
+                true
+            }
+        });
+        /*
+         */
+    }
+
+    pub fn drop(&mut self, ticket: KbOracleTicket) {
+        self.temporal_logs.retain(|active_key| {
+            let (active_ticket, (_active_layer, _key, _keys)) = active_key.clone();
+
+            active_ticket != ticket
+        });
+        /*
+         */
+    }
+
+    pub fn drop_index(&mut self, index: usize) {
+        let mut idx = 0usize;
+        self.temporal_logs.retain(|_| {
+            if idx == index {
+                return false;
+            }
+            idx += 1;
+            true
+        });
+    }
+
+    pub fn drop_indexes(&mut self, indexes: Vec<usize>) {
+        indexes.iter().for_each(|&idx| self.drop_index(idx))
+    }
+
+    pub fn clear(&mut self) {
+        self.temporal_logs.clear();
+        self.current_ticket = 0;
+    }
+
+    /// forget every key and render the reports releasing them.
+    pub fn release(&mut self) -> Vec<KbOracleReports> {
+        self.clear();
+
+        let mut reports = vec![KbOracleReports::init()];
+        if self.consumer_usage != 0 {
+            self.consumer_usage = 0;
+            reports.push(KbOracleReports::Consumer(MediaKeyboardReport {
+                usage_id: 0,
+            }));
+        }
+        if self.system_usage != 0 {
+            self.system_usage = 0;
+            reports.push(KbOracleReports::System(SystemControlReport { usage_id: 0 }));
+        }
+        reports
+    }
+
+    /// the logs to render, less the skipped tickets.
+    fn reported_logs(&self) -> Vec<(KbOracleTicket, KbOracleTemporalLog)> {
+        self.temporal_logs
+            .iter()
+            .filter(|l| !self.skipped_tickets.contains(&l.0))
+            .cloned()
+            .collect()
+    }
+
+    pub fn generate_reports(
+        &mut self,
+        _for_scan: (Vec<LayerMask>, Vec<ScanCode>),
+    ) -> Vec<KbOracleReports> {
+        self.temporal_logs.reverse();
+        let reported = self.reported_logs();
+
+        // a key rendering to anything but keyboard usages is kept out of the
+        // keyboard report, its usages go out in their own report.
+        let keyboard_log = reported
+            .iter()
+            .map(|l| l.1 .2 .2)
+            .filter(|entrants| {
+                entrants
+                    .iter()
+                    .all(|entrant| matches!(entrant, KeyboardMapEntrant::Keyboard(_)))
+            })
+            .flat_map(|entrants| entrants.iter().cloned())
+            .collect::<Vec<KeyboardMapEntrant>>();
+
+        let consumer_log = reported
+            .iter()
+            .flat_map(|l| l.1 .2 .2.iter())
+            .filter(|entrant| matches!(entrant, KeyboardMapEntrant::Consumer(_)))
+            .cloned()
+            .collect::<Vec<KeyboardMapEntrant>>();
+
+        let system_log = reported
+            .iter()
+            .flat_map(|l| l.1 .2 .2.iter())
+            .filter(|entrant| matches!(entrant, KeyboardMapEntrant::System(_)))
+            .cloned()
+            .collect::<Vec<KeyboardMapEntrant>>();
+
+        let mut reports: Vec<KbOracleReports> = Vec::new();
+
+        for keys in vec![keyboard_log].iter() {
+            // every held key goes into the bitmap, the firmware packs it into
+            // a 6kro report for boot protocol hosts, see `NkroReport::to_boot`.
+            let usages = keys.iter().map(|key| Into::<u8>::into(key.clone()));
+            let report = NkroReport::from_usages(usages);
+
+            reports.push(KbOracleReports::Keyboard(report))
+        }
+
+        reports = reports
+            .clone()
+            .iter()
+            .filter(|report| !report.is_empty())
+            .cloned()
+            .collect::<Vec<KbOracleReports>>();
+
+        // consumer and system control state goes out on change only, so a held
+        // media key neither repeats nor displaces the keyboard report and its
+        // release is reported as an empty report. either report carries a
+        // single usage, a key maps at most one of each (see
+        // `kbmap::storage::fits_reports`) and of several such keys held at
+        // once only one is reported.
+        let consumer_usage = consumer_log
+            .first()
+            .map(|c| c.clone().into())
+            .unwrap_or(0u16);
+        if consumer_usage != self.consumer_usage {
+            self.consumer_usage = consumer_usage;
+            reports.push(KbOracleReports::Consumer(MediaKeyboardReport {
+                usage_id: consumer_usage,
+            }))
+        }
+
+        let system_usage = system_log.first().map(|s| s.clone().into()).unwrap_or(0u8);
+        if system_usage != self.system_usage {
+            self.system_usage = system_usage;
+            reports.push(KbOracleReports::System(SystemControlReport {
+                usage_id: system_usage,
+            }))
+        }
+
+        // self.clear();
+        reports
+    }
+}
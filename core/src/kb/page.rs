@@ -0,0 +1,301 @@
+//! the usb hid usage pages keys render to, named after the hid usage tables
+//! (and `usbd_human_interface_device::page`, which the keymap used to name its
+//! usages by). a usage is a plain number so that any of them, named here or
+//! not, round-trips through a stored keymap.
+
+/// a usage of the keyboard/keypad page (0x07).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Keyboard(pub u8);
+
+#[allow(non_upper_case_globals)]
+impl Keyboard {
+    pub const NoEventIndicated: Keyboard = Keyboard(0x00);
+    pub const ErrorRollOver: Keyboard = Keyboard(0x01);
+    pub const POSTFail: Keyboard = Keyboard(0x02);
+    pub const ErrorUndefine: Keyboard = Keyboard(0x03);
+    pub const A: Keyboard = Keyboard(0x04);
+    pub const B: Keyboard = Keyboard(0x05);
+    pub const C: Keyboard = Keyboard(0x06);
+    pub const D: Keyboard = Keyboard(0x07);
+    pub const E: Keyboard = Keyboard(0x08);
+    pub const F: Keyboard = Keyboard(0x09);
+    pub const G: Keyboard = Keyboard(0x0A);
+    pub const H: Keyboard = Keyboard(0x0B);
+    pub const I: Keyboard = Keyboard(0x0C);
+    pub const J: Keyboard = Keyboard(0x0D);
+    pub const K: Keyboard = Keyboard(0x0E);
+    pub const L: Keyboard = Keyboard(0x0F);
+    pub const M: Keyboard = Keyboard(0x10);
+    pub const N: Keyboard = Keyboard(0x11);
+    pub const O: Keyboard = Keyboard(0x12);
+    pub const P: Keyboard = Keyboard(0x13);
+    pub const Q: Keyboard = Keyboard(0x14);
+    pub const R: Keyboard = Keyboard(0x15);
+    pub const S: Keyboard = Keyboard(0x16);
+    pub const T: Keyboard = Keyboard(0x17);
+    pub const U: Keyboard = Keyboard(0x18);
+    pub const V: Keyboard = Keyboard(0x19);
+    pub const W: Keyboard = Keyboard(0x1A);
+    pub const X: Keyboard = Keyboard(0x1B);
+    pub const Y: Keyboard = Keyboard(0x1C);
+    pub const Z: Keyboard = Keyboard(0x1D);
+    pub const Keyboard1: Keyboard = Keyboard(0x1E);
+    pub const Keyboard2: Keyboard = Keyboard(0x1F);
+    pub const Keyboard3: Keyboard = Keyboard(0x20);
+    pub const Keyboard4: Keyboard = Keyboard(0x21);
+    pub const Keyboard5: Keyboard = Keyboard(0x22);
+    pub const Keyboard6: Keyboard = Keyboard(0x23);
+    pub const Keyboard7: Keyboard = Keyboard(0x24);
+    pub const Keyboard8: Keyboard = Keyboard(0x25);
+    pub const Keyboard9: Keyboard = Keyboard(0x26);
+    pub const Keyboard0: Keyboard = Keyboard(0x27);
+    pub const ReturnEnter: Keyboard = Keyboard(0x28);
+    pub const Escape: Keyboard = Keyboard(0x29);
+    pub const DeleteBackspace: Keyboard = Keyboard(0x2A);
+    pub const Tab: Keyboard = Keyboard(0x2B);
+    pub const Space: Keyboard = Keyboard(0x2C);
+    pub const Minus: Keyboard = Keyboard(0x2D);
+    pub const Equal: Keyboard = Keyboard(0x2E);
+    pub const LeftBrace: Keyboard = Keyboard(0x2F);
+    pub const RightBrace: Keyboard = Keyboard(0x30);
+    pub const Backslash: Keyboard = Keyboard(0x31);
+    pub const NonUSHash: Keyboard = Keyboard(0x32);
+    pub const Semicolon: Keyboard = Keyboard(0x33);
+    pub const Apostrophe: Keyboard = Keyboard(0x34);
+    pub const Grave: Keyboard = Keyboard(0x35);
+    pub const Comma: Keyboard = Keyboard(0x36);
+    pub const Dot: Keyboard = Keyboard(0x37);
+    pub const ForwardSlash: Keyboard = Keyboard(0x38);
+    pub const CapsLock: Keyboard = Keyboard(0x39);
+    pub const F1: Keyboard = Keyboard(0x3A);
+    pub const F2: Keyboard = Keyboard(0x3B);
+    pub const F3: Keyboard = Keyboard(0x3C);
+    pub const F4: Keyboard = Keyboard(0x3D);
+    pub const F5: Keyboard = Keyboard(0x3E);
+    pub const F6: Keyboard = Keyboard(0x3F);
+    pub const F7: Keyboard = Keyboard(0x40);
+    pub const F8: Keyboard = Keyboard(0x41);
+    pub const F9: Keyboard = Keyboard(0x42);
+    pub const F10: Keyboard = Keyboard(0x43);
+    pub const F11: Keyboard = Keyboard(0x44);
+    pub const F12: Keyboard = Keyboard(0x45);
+    pub const PrintScreen: Keyboard = Keyboard(0x46);
+    pub const ScrollLock: Keyboard = Keyboard(0x47);
+    pub const Pause: Keyboard = Keyboard(0x48);
+    pub const Insert: Keyboard = Keyboard(0x49);
+    pub const Home: Keyboard = Keyboard(0x4A);
+    pub const PageUp: Keyboard = Keyboard(0x4B);
+    pub const DeleteForward: Keyboard = Keyboard(0x4C);
+    pub const End: Keyboard = Keyboard(0x4D);
+    pub const PageDown: Keyboard = Keyboard(0x4E);
+    pub const RightArrow: Keyboard = Keyboard(0x4F);
+    pub const LeftArrow: Keyboard = Keyboard(0x50);
+    pub const DownArrow: Keyboard = Keyboard(0x51);
+    pub const UpArrow: Keyboard = Keyboard(0x52);
+    pub const KeypadNumLockAndClear: Keyboard = Keyboard(0x53);
+    pub const KeypadDivide: Keyboard = Keyboard(0x54);
+    pub const KeypadMultiply: Keyboard = Keyboard(0x55);
+    pub const KeypadSubtract: Keyboard = Keyboard(0x56);
+    pub const KeypadAdd: Keyboard = Keyboard(0x57);
+    pub const KeypadEnter: Keyboard = Keyboard(0x58);
+    pub const Keypad1: Keyboard = Keyboard(0x59);
+    pub const Keypad2: Keyboard = Keyboard(0x5A);
+    pub const Keypad3: Keyboard = Keyboard(0x5B);
+    pub const Keypad4: Keyboard = Keyboard(0x5C);
+    pub const Keypad5: Keyboard = Keyboard(0x5D);
+    pub const Keypad6: Keyboard = Keyboard(0x5E);
+    pub const Keypad7: Keyboard = Keyboard(0x5F);
+    pub const Keypad8: Keyboard = Keyboard(0x60);
+    pub const Keypad9: Keyboard = Keyboard(0x61);
+    pub const Keypad0: Keyboard = Keyboard(0x62);
+    pub const KeypadDot: Keyboard = Keyboard(0x63);
+    pub const NonUSBackslash: Keyboard = Keyboard(0x64);
+    pub const Application: Keyboard = Keyboard(0x65);
+    pub const Power: Keyboard = Keyboard(0x66);
+    pub const KeypadEqual: Keyboard = Keyboard(0x67);
+    pub const F13: Keyboard = Keyboard(0x68);
+    pub const F14: Keyboard = Keyboard(0x69);
+    pub const F15: Keyboard = Keyboard(0x6A);
+    pub const F16: Keyboard = Keyboard(0x6B);
+    pub const F17: Keyboard = Keyboard(0x6C);
+    pub const F18: Keyboard = Keyboard(0x6D);
+    pub const F19: Keyboard = Keyboard(0x6E);
+    pub const F20: Keyboard = Keyboard(0x6F);
+    pub const F21: Keyboard = Keyboard(0x70);
+    pub const F22: Keyboard = Keyboard(0x71);
+    pub const F23: Keyboard = Keyboard(0x72);
+    pub const F24: Keyboard = Keyboard(0x73);
+    pub const Execute: Keyboard = Keyboard(0x74);
+    pub const Help: Keyboard = Keyboard(0x75);
+    pub const Menu: Keyboard = Keyboard(0x76);
+    pub const Select: Keyboard = Keyboard(0x77);
+    pub const Stop: Keyboard = Keyboard(0x78);
+    pub const Again: Keyboard = Keyboard(0x79);
+    pub const Undo: Keyboard = Keyboard(0x7A);
+    pub const Cut: Keyboard = Keyboard(0x7B);
+    pub const Copy: Keyboard = Keyboard(0x7C);
+    pub const Paste: Keyboard = Keyboard(0x7D);
+    pub const Find: Keyboard = Keyboard(0x7E);
+    pub const Mute: Keyboard = Keyboard(0x7F);
+    pub const VolumeUp: Keyboard = Keyboard(0x80);
+    pub const VolumeDown: Keyboard = Keyboard(0x81);
+    pub const LockingCapsLock: Keyboard = Keyboard(0x82);
+    pub const LockingNumLock: Keyboard = Keyboard(0x83);
+    pub const LockingScrollLock: Keyboard = Keyboard(0x84);
+    pub const KeypadComma: Keyboard = Keyboard(0x85);
+    pub const KeypadEqualSign: Keyboard = Keyboard(0x86);
+    pub const International1: Keyboard = Keyboard(0x87);
+    pub const International2: Keyboard = Keyboard(0x88);
+    pub const International3: Keyboard = Keyboard(0x89);
+    pub const International4: Keyboard = Keyboard(0x8A);
+    pub const International5: Keyboard = Keyboard(0x8B);
+    pub const International6: Keyboard = Keyboard(0x8C);
+    pub const International7: Keyboard = Keyboard(0x8D);
+    pub const International8: Keyboard = Keyboard(0x8E);
+    pub const International9: Keyboard = Keyboard(0x8F);
+    pub const LANG1: Keyboard = Keyboard(0x90);
+    pub const LANG2: Keyboard = Keyboard(0x91);
+    pub const LANG3: Keyboard = Keyboard(0x92);
+    pub const LANG4: Keyboard = Keyboard(0x93);
+    pub const LANG5: Keyboard = Keyboard(0x94);
+    pub const LANG6: Keyboard = Keyboard(0x95);
+    pub const LANG7: Keyboard = Keyboard(0x96);
+    pub const LANG8: Keyboard = Keyboard(0x97);
+    pub const LANG9: Keyboard = Keyboard(0x98);
+    pub const AlternateErase: Keyboard = Keyboard(0x99);
+    pub const SysReqAttention: Keyboard = Keyboard(0x9A);
+    pub const Cancel: Keyboard = Keyboard(0x9B);
+    pub const Clear: Keyboard = Keyboard(0x9C);
+    pub const Prior: Keyboard = Keyboard(0x9D);
+    pub const Return: Keyboard = Keyboard(0x9E);
+    pub const Separator: Keyboard = Keyboard(0x9F);
+    pub const Out: Keyboard = Keyboard(0xA0);
+    pub const Oper: Keyboard = Keyboard(0xA1);
+    pub const ClearAgain: Keyboard = Keyboard(0xA2);
+    pub const CrSelProps: Keyboard = Keyboard(0xA3);
+    pub const ExSel: Keyboard = Keyboard(0xA4);
+    pub const Keypad00: Keyboard = Keyboard(0xB0);
+    pub const Keypad000: Keyboard = Keyboard(0xB1);
+    pub const ThousandsSeparator: Keyboard = Keyboard(0xB2);
+    pub const DecimalSeparator: Keyboard = Keyboard(0xB3);
+    pub const CurrencyUnit: Keyboard = Keyboard(0xB4);
+    pub const CurrencySubunit: Keyboard = Keyboard(0xB5);
+    pub const KeypadOpenParens: Keyboard = Keyboard(0xB6);
+    pub const KeypadCloseParens: Keyboard = Keyboard(0xB7);
+    pub const KeypadOpenBrace: Keyboard = Keyboard(0xB8);
+    pub const KeypadCloseBrace: Keyboard = Keyboard(0xB9);
+    pub const KeypadTab: Keyboard = Keyboard(0xBA);
+    pub const KeypadBackspace: Keyboard = Keyboard(0xBB);
+    pub const KeypadA: Keyboard = Keyboard(0xBC);
+    pub const KeypadB: Keyboard = Keyboard(0xBD);
+    pub const KeypadC: Keyboard = Keyboard(0xBE);
+    pub const KeypadD: Keyboard = Keyboard(0xBF);
+    pub const KeypadE: Keyboard = Keyboard(0xC0);
+    pub const KeypadF: Keyboard = Keyboard(0xC1);
+    pub const KeypadXor: Keyboard = Keyboard(0xC2);
+    pub const KeypadCaret: Keyboard = Keyboard(0xC3);
+    pub const KeypadPercentage: Keyboard = Keyboard(0xC4);
+    pub const KeypadLess: Keyboard = Keyboard(0xC5);
+    pub const KeypadGreater: Keyboard = Keyboard(0xC6);
+    pub const KeypadAmpersand: Keyboard = Keyboard(0xC7);
+    pub const KeypadDoubleAmpersand: Keyboard = Keyboard(0xC8);
+    pub const KeypadBar: Keyboard = Keyboard(0xC9);
+    pub const KeypadDoubleBar: Keyboard = Keyboard(0xCA);
+    pub const KeypadColon: Keyboard = Keyboard(0xCB);
+    pub const KeypadHash: Keyboard = Keyboard(0xCC);
+    pub const KeypadSpace: Keyboard = Keyboard(0xCD);
+    pub const KeypadAt: Keyboard = Keyboard(0xCE);
+    pub const KeypadExclamation: Keyboard = Keyboard(0xCF);
+    pub const KeypadMemoryStore: Keyboard = Keyboard(0xD0);
+    pub const KeypadMemoryRecall: Keyboard = Keyboard(0xD1);
+    pub const KeypadMemoryClear: Keyboard = Keyboard(0xD2);
+    pub const KeypadMemoryAdd: Keyboard = Keyboard(0xD3);
+    pub const KeypadMemorySubtract: Keyboard = Keyboard(0xD4);
+    pub const KeypadMemoryMultiply: Keyboard = Keyboard(0xD5);
+    pub const KeypadMemoryDivide: Keyboard = Keyboard(0xD6);
+    pub const KeypadPlusMinus: Keyboard = Keyboard(0xD7);
+    pub const KeypadClear: Keyboard = Keyboard(0xD8);
+    pub const KeypadClearEntry: Keyboard = Keyboard(0xD9);
+    pub const KeypadBinary: Keyboard = Keyboard(0xDA);
+    pub const KeypadOctal: Keyboard = Keyboard(0xDB);
+    pub const KeypadDecimal: Keyboard = Keyboard(0xDC);
+    pub const KeypadHexadecimal: Keyboard = Keyboard(0xDD);
+    pub const LeftControl: Keyboard = Keyboard(0xE0);
+    pub const LeftShift: Keyboard = Keyboard(0xE1);
+    pub const LeftAlt: Keyboard = Keyboard(0xE2);
+    pub const LeftGUI: Keyboard = Keyboard(0xE3);
+    pub const RightControl: Keyboard = Keyboard(0xE4);
+    pub const RightShift: Keyboard = Keyboard(0xE5);
+    pub const RightAlt: Keyboard = Keyboard(0xE6);
+    pub const RightGUI: Keyboard = Keyboard(0xE7);
+}
+
+impl From<u8> for Keyboard {
+    fn from(usage: u8) -> Keyboard {
+        Keyboard(usage)
+    }
+}
+
+impl From<Keyboard> for u8 {
+    fn from(keyboard: Keyboard) -> u8 {
+        keyboard.0
+    }
+}
+
+/// a usage of the consumer page (0x0C), only the common ones are named.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Consumer(pub u16);
+
+#[allow(non_upper_case_globals)]
+impl Consumer {
+    pub const Unassigned: Consumer = Consumer(0x0000);
+    pub const ConsumerControl: Consumer = Consumer(0x0001);
+    pub const Power: Consumer = Consumer(0x0030);
+    pub const Reset: Consumer = Consumer(0x0031);
+    pub const Sleep: Consumer = Consumer(0x0032);
+    pub const Menu: Consumer = Consumer(0x0040);
+    pub const DisplayBrightnessIncrement: Consumer = Consumer(0x006F);
+    pub const DisplayBrightnessDecrement: Consumer = Consumer(0x0070);
+    pub const Play: Consumer = Consumer(0x00B0);
+    pub const Pause: Consumer = Consumer(0x00B1);
+    pub const Record: Consumer = Consumer(0x00B2);
+    pub const FastForward: Consumer = Consumer(0x00B3);
+    pub const Rewind: Consumer = Consumer(0x00B4);
+    pub const ScanNextTrack: Consumer = Consumer(0x00B5);
+    pub const ScanPreviousTrack: Consumer = Consumer(0x00B6);
+    pub const Stop: Consumer = Consumer(0x00B7);
+    pub const Eject: Consumer = Consumer(0x00B8);
+    pub const RandomPlay: Consumer = Consumer(0x00B9);
+    pub const Repeat: Consumer = Consumer(0x00BC);
+    pub const PlayPause: Consumer = Consumer(0x00CD);
+    pub const Mute: Consumer = Consumer(0x00E2);
+    pub const BassBoost: Consumer = Consumer(0x00E5);
+    pub const Loudness: Consumer = Consumer(0x00E7);
+    pub const VolumeIncrement: Consumer = Consumer(0x00E9);
+    pub const VolumeDecrement: Consumer = Consumer(0x00EA);
+    pub const ALConsumerControlConfiguration: Consumer = Consumer(0x0183);
+    pub const ALEmailReader: Consumer = Consumer(0x018A);
+    pub const ALCalculator: Consumer = Consumer(0x0192);
+    pub const ALLocalMachineBrowser: Consumer = Consumer(0x0194);
+    pub const ACSearch: Consumer = Consumer(0x0221);
+    pub const ACHome: Consumer = Consumer(0x0223);
+    pub const ACBack: Consumer = Consumer(0x0224);
+    pub const ACForward: Consumer = Consumer(0x0225);
+    pub const ACStop: Consumer = Consumer(0x0226);
+    pub const ACRefresh: Consumer = Consumer(0x0227);
+    pub const ACBookmarks: Consumer = Consumer(0x022A);
+}
+
+impl From<u16> for Consumer {
+    fn from(usage: u16) -> Consumer {
+        Consumer(usage)
+    }
+}
+
+impl From<Consumer> for u16 {
+    fn from(consumer: Consumer) -> u16 {
+        consumer.0
+    }
+}
@@ -0,0 +1,118 @@
+//! the keyboard report as the oracle renders it, an nkro bitmap of every held
+//! key, and its 6kro boot protocol form.
+
+/// the keyboard usages the bitmap covers, everything below the modifiers
+/// (`0xE0..=0xE7`) which have a byte of their own.
+pub const NKRO_USAGES: usize = 0xE0;
+pub const NKRO_BITMAP_LEN: usize = NKRO_USAGES / 8;
+/// the report on the wire: the modifier byte then the bitmap.
+pub const NKRO_REPORT_LEN: usize = 1 + NKRO_BITMAP_LEN;
+/// the keys a boot report has room for.
+pub const BOOT_KEYS: usize = 6;
+/// a boot report as sent under the boot protocol, without a report id.
+pub const BOOT_REPORT_LEN: usize = 2 + BOOT_KEYS;
+
+/// the keyboard usage reported in every slot of a boot report that has more
+/// keys held than it has room for, or ghosted keys.
+pub const ERROR_ROLL_OVER: u8 = 0x01;
+
+const MODIFIER_USAGES: core::ops::RangeInclusive<u8> = 0xE0..=0xE7;
+
+/// the 6kro keyboard report of the boot protocol, hid 1.11 appendix b.1.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyboardReport {
+    pub modifier: u8,
+    pub reserved: u8,
+    pub leds: u8,
+    pub keycodes: [u8; BOOT_KEYS],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct NkroReport {
+    pub modifier: u8,
+    pub keys: [u8; NKRO_BITMAP_LEN],
+    /// the matrix is ghosted, see `KeyScan::suppress_ghosts`. the bitmap
+    /// has no room for an error so it keeps the keys from before.
+    pub roll_over: bool,
+}
+
+impl NkroReport {
+    pub const fn empty() -> Self {
+        Self {
+            modifier: 0,
+            keys: [0u8; NKRO_BITMAP_LEN],
+            roll_over: false,
+        }
+    }
+
+    /// a report holding `usages`, the modifier usages among them are folded
+    /// into `modifier`.
+    pub fn from_usages(usages: impl Iterator<Item = u8>) -> Self {
+        let mut report = Self::empty();
+        usages.for_each(|usage| report.insert(usage));
+        report
+    }
+
+    pub fn insert(&mut self, usage: u8) {
+        match usage {
+            _ if MODIFIER_USAGES.contains(&usage) => self.modifier |= 1 << (usage - 0xE0),
+            // no event and the error codes aren't keys.
+            0x00..=0x03 => {}
+            _ if (usage as usize) < NKRO_USAGES => {
+                self.keys[usage as usize / 8] |= 1 << (usage % 8)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn contains(&self, usage: u8) -> bool {
+        (usage as usize) < NKRO_USAGES && self.keys[usage as usize / 8] & (1 << (usage % 8)) != 0
+    }
+
+    /// the held keys, less modifiers, in usage order.
+    pub fn keycodes(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..NKRO_USAGES as u8).filter(|usage| self.contains(*usage))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.roll_over && self.modifier == 0 && self.keys.iter().all(|byte| *byte == 0)
+    }
+
+    pub fn to_bytes(&self) -> [u8; NKRO_REPORT_LEN] {
+        let mut bytes = [0u8; NKRO_REPORT_LEN];
+        bytes[0] = self.modifier;
+        bytes[1..].copy_from_slice(&self.keys);
+        bytes
+    }
+
+    /// the 6kro report for boot protocol hosts. past six keys, or with ghosted
+    /// keys, every slot is `ERROR_ROLL_OVER` rather than an arbitrary six of
+    /// them, the modifiers are still reported.
+    pub fn to_boot(&self) -> KeyboardReport {
+        let mut keycodes = [0u8; BOOT_KEYS];
+        if self.roll_over || self.keycodes().count() > BOOT_KEYS {
+            keycodes = [ERROR_ROLL_OVER; BOOT_KEYS];
+        } else {
+            keycodes
+                .iter_mut()
+                .zip(self.keycodes())
+                .for_each(|(slot, usage)| *slot = usage);
+        }
+
+        KeyboardReport {
+            modifier: self.modifier,
+            reserved: 0,
+            leds: 0,
+            keycodes,
+        }
+    }
+}
+
+/// a boot report as sent under the boot protocol.
+pub fn boot_bytes(report: &KeyboardReport) -> [u8; BOOT_REPORT_LEN] {
+    let mut bytes = [0u8; BOOT_REPORT_LEN];
+    bytes[0] = report.modifier;
+    bytes[1] = report.reserved;
+    bytes[2..].copy_from_slice(&report.keycodes);
+    bytes
+}
@@ -3,17 +3,15 @@ use core::ops::{Add, Sub};
 use alloc::vec;
 use alloc::vec::Vec;
 
-use usbd_hid::descriptor::KeyboardReport;
-
-use crate::drivers::shared::kb::Key;
+use crate::shared::kb::{KeyAction, KeyEvent, LayerMask, ScanCode};
 
 use super::{
-    input::{KbDriverInput, KeyEvent, Modifiers},
+    input::Modifiers,
     kbmap::KeyboardMapEntrant,
     oracle::{KbOracle, KbOracleReports},
 };
 
-pub type ActiveKey = (u8, u8, Vec<KeyboardMapEntrant>);
+pub type ActiveKey = (LayerMask, ScanCode, &'static [KeyboardMapEntrant]);
 pub type ActiveKeys = Vec<ActiveKey>;
 
 #[derive(Clone)]
@@ -42,32 +40,35 @@ impl KeyState {
         self.oracle.clear()
     }
 
+    /// clear and render the reports releasing whatever was held.
+    pub fn release(&mut self) -> Vec<KbOracleReports> {
+        self.active_keys = Vec::new();
+        self.oracle.release()
+    }
+
     pub fn handle_modifier_event(&mut self, modifier_scan_codes: Vec<Modifiers>) -> Modifiers {
-        let layer: u8 = modifier_scan_codes.iter().fold(0u8, |acc, m| {
-            let m: u8 = m.clone().into();
-            acc + m
-        });
-        layer.into()
+        modifier_scan_codes
+            .iter()
+            .fold(Modifiers::BARE, |acc, &m| acc | m)
     }
 
     pub fn handle_key_event(
         &mut self,
-        modifier_code: u8,
+        layer: LayerMask,
         key_event_inp: Option<KeyEvent>,
     ) -> (bool, Option<Self>) {
         if let Some(key_event) = key_event_inp {
-            let (key, _) = key_event.clone();
-            let scan_code = key.key.parse::<u8>().unwrap();
-            let active_key = (modifier_code, scan_code, key.usb_hid);
+            let scan_code = key_event.scan_code;
+            let active_key = (layer, scan_code, key_event.usb_hid);
 
             self.active_keys = vec![active_key.clone()];
             /*
             self.oracle
-                .record((modifier_code, key_event.clone()), active_key.clone());
+                .record(key_event.clone(), active_key.clone());
             */
 
             (true, {
-                if modifier_code != 0 && scan_code != 0 {
+                if !layer.is_bare() && !scan_code.is_none() {
                     Some(self.clone())
                 } else {
                     None
@@ -78,42 +79,44 @@ impl KeyState {
         }
     }
 
-    pub fn last_pressed(&self, modifier_code: u8, scan_code: u8) -> bool {
+    pub fn last_pressed(&self, layer: LayerMask, scan_code: ScanCode) -> bool {
         match self.active_keys.iter().last() {
-            Some(k) => k.0 == modifier_code && k.1 == scan_code,
+            Some(k) => k.0 == layer && k.1 == scan_code,
             None => false,
         }
     }
 
-    pub fn previously_pressed(&self, modifier_code: u8, scan_code: u8) -> bool {
+    pub fn previously_pressed(&self, layer: LayerMask, scan_code: ScanCode) -> bool {
         self.active_keys
             .iter()
-            .any(|k| k.0 == modifier_code && k.1 == scan_code)
+            .any(|k| k.0 == layer && k.1 == scan_code)
     }
 
-    pub fn record_key_event(&mut self, modifier_code: u8, scan_code: u8, key_event: KeyEvent) {
-        self.oracle.record(
-            (modifier_code, key_event.clone()),
-            (modifier_code, scan_code, key_event.clone().0.usb_hid),
-        );
+    pub fn record_key_event(&mut self, layer: LayerMask, scan_code: ScanCode, key_event: KeyEvent) {
+        let usb_hid = key_event.usb_hid;
+        self.oracle.record(key_event, (layer, scan_code, usb_hid));
     }
 
-    pub fn remove_key_event(&mut self, modifier_code: u8, scan_code: u8, key_event: KeyEvent) {
+    pub fn remove_key_event(
+        &mut self,
+        layer: LayerMask,
+        scan_code: ScanCode,
+        _key_event: KeyEvent,
+    ) {
         /*
         self.oracle.remove(
-            (modifier_code, key_event.clone()),
-            (
-                modifier_code.clone(),
-                key_event.clone().0.key.parse().unwrap(),
-                key_event.clone().0.usb_hid,
-            ),
+            key_event.clone(),
+            (layer, key_event.scan_code, key_event.usb_hid),
         );
         */
         self.active_keys
-            .retain(|k| !(k.0 == modifier_code && k.1 == scan_code))
+            .retain(|k| !(k.0 == layer && k.1 == scan_code))
     }
 
-    pub fn generate_reports(&mut self, for_scan: (Vec<u8>, Vec<u8>)) -> Vec<KbOracleReports> {
+    pub fn generate_reports(
+        &mut self,
+        for_scan: (Vec<LayerMask>, Vec<ScanCode>),
+    ) -> Vec<KbOracleReports> {
         self.oracle.generate_reports(for_scan)
     }
 }
@@ -131,19 +134,14 @@ impl Add for KeyState {
         for (_i, right_active_key) in rhs.active_keys.iter().enumerate() {
             new_self.active_keys.push(right_active_key.clone());
 
-            let (layer, scan_code, keys) = (
-                right_active_key.0.clone(),
-                right_active_key.1.clone(),
-                right_active_key.2.clone(),
-            );
+            let (layer, scan_code, keys) = right_active_key.clone();
             new_self.oracle.record(
-                (
+                KeyEvent {
+                    action: KeyAction::Press,
                     layer,
-                    (
-                        Key::define(scan_code, scan_code, keys),
-                        KbDriverInput::KeyDown,
-                    ),
-                ),
+                    scan_code,
+                    usb_hid: keys,
+                },
                 right_active_key.clone(),
             );
         }
@@ -0,0 +1,17 @@
+//! hardware independent keyboard logic shared by the rp2040 firmware and the
+//! host tools: the matrix decoder, debouncer, keymap, `KeyState`, `KbOracle`, the
+//! settings log, the crash log and health.
+#![no_std]
+
+extern crate alloc;
+
+#[macro_use]
+mod log;
+
+pub mod config;
+pub mod crash;
+pub mod health;
+pub mod kb;
+pub mod settings;
+pub mod shared;
+pub mod utils;
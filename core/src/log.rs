@@ -0,0 +1,26 @@
+//! the core logs through defmt only when the firmware asks for it (`defmt`
+//! feature), otherwise the diagnostics compile away so the host tools don't
+//! need a defmt global logger. the arguments are still borrowed either way so
+//! values that only exist to be logged don't trip `unused_variables`.
+
+macro_rules! error {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+        #[cfg(feature = "defmt")]
+        defmt::error!($fmt $(, $arg)*);
+        #[cfg(not(feature = "defmt"))]
+        {
+            $(let _ = &$arg;)*
+        }
+    }};
+}
+
+macro_rules! info {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+        #[cfg(feature = "defmt")]
+        defmt::info!($fmt $(, $arg)*);
+        #[cfg(not(feature = "defmt"))]
+        {
+            $(let _ = &$arg;)*
+        }
+    }};
+}
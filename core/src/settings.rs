@@ -0,0 +1,478 @@
+//! the firmware's tunables (debounce, scan rate, profile, os mode, ..), kept in
+//! two reserved flash sectors so they survive a reflash of the program image.
+//!
+//! a sector is a log: a header, then one record appended per change. the
+//! latest record of a setting wins. once the active sector is full every
+//! current value is rewritten to the other sector and its header goes last,
+//! so a power loss anywhere leaves the previous sector intact. each sector is
+//! erased once per `LOG_SLOTS` changes rather than once per change.
+//!
+//! all integers are little endian, every slot is `LOG_SLOT_LEN` bytes.
+//!
+//! | slots | header                                |
+//! |-------|---------------------------------------|
+//! | 2     | `SETTINGS_MAGIC`, sequence (u32), crc-32 of both, zero |
+//!
+//! | slot | record                                 |
+//! |------|----------------------------------------|
+//! | 1    | `Setting`, zero, value (u16), crc-32 of the first 4 bytes |
+//!
+//! a record that fails its crc (torn by a power loss) is skipped.
+
+use crate::kb::decoder::DebounceMode;
+use crate::utils::crc::crc32;
+
+pub const SETTINGS_MAGIC: [u8; 4] = *b"A2ST";
+pub const SETTINGS_SECTOR_LEN: usize = 4096;
+pub const LOG_SLOT_LEN: usize = 8;
+const LOG_SLOTS: usize = SETTINGS_SECTOR_LEN / LOG_SLOT_LEN;
+const HEADER_SLOTS: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Setting {
+    /// the release window of the key matrix, see `kb::decoder::Debounce`.
+    /// a tick lasts `ScanRateMs`.
+    DebounceReleaseTicks = 0x01,
+    /// the period of matrix scans, kept by a timer alarm.
+    ScanRateMs = 0x02,
+    /// the keymap profile to boot into.
+    Profile = 0x03,
+    /// the os the host runs, see `OsMode`.
+    OsMode = 0x04,
+    /// how long the scan loop may stall before the watchdog resets the board.
+    WatchdogMs = 0x05,
+    /// the key matrix's `DebounceMode`.
+    DebounceMode = 0x06,
+    DebouncePressTicks = 0x07,
+    /// the modifier lines' `DebounceMode`, their switches chatter differently.
+    ModifierDebounceMode = 0x08,
+    ModifierPressTicks = 0x09,
+    ModifierReleaseTicks = 0x0A,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 10] = [
+        Setting::DebounceReleaseTicks,
+        Setting::ScanRateMs,
+        Setting::Profile,
+        Setting::OsMode,
+        Setting::WatchdogMs,
+        Setting::DebounceMode,
+        Setting::DebouncePressTicks,
+        Setting::ModifierDebounceMode,
+        Setting::ModifierPressTicks,
+        Setting::ModifierReleaseTicks,
+    ];
+
+    pub fn get(id: u8) -> Option<Setting> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|setting| *setting as u8 == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Setting::DebounceReleaseTicks => "debounce_release_ticks",
+            Setting::ScanRateMs => "scan_rate_ms",
+            Setting::Profile => "profile",
+            Setting::OsMode => "os_mode",
+            Setting::WatchdogMs => "watchdog_ms",
+            Setting::DebounceMode => "debounce_mode",
+            Setting::DebouncePressTicks => "debounce_press_ticks",
+            Setting::ModifierDebounceMode => "modifier_debounce_mode",
+            Setting::ModifierPressTicks => "modifier_press_ticks",
+            Setting::ModifierReleaseTicks => "modifier_release_ticks",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Setting> {
+        // the release window's name before the press window was added.
+        if name == "debounce_ticks" {
+            return Some(Setting::DebounceReleaseTicks);
+        }
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|setting| setting.name() == name)
+    }
+
+    /// the value until one is stored.
+    pub fn default_value(self) -> u16 {
+        match self {
+            Setting::DebounceReleaseTicks => 1,
+            Setting::ScanRateMs => 5,
+            Setting::Profile => 0,
+            Setting::OsMode => OsMode::Mac as u16,
+            Setting::WatchdogMs => 1_000,
+            Setting::DebounceMode => DebounceMode::EagerPress as u16,
+            Setting::DebouncePressTicks => 1,
+            Setting::ModifierDebounceMode => DebounceMode::EagerPress as u16,
+            Setting::ModifierPressTicks => 1,
+            Setting::ModifierReleaseTicks => 1,
+        }
+    }
+
+    pub fn accepts(self, value: u16) -> bool {
+        match self {
+            // a window of 0 would keep an eager key from ever reading pressed.
+            Setting::DebounceReleaseTicks
+            | Setting::DebouncePressTicks
+            | Setting::ModifierPressTicks
+            | Setting::ModifierReleaseTicks => (1..=u8::MAX as u16).contains(&value),
+            Setting::ScanRateMs => (1..=50).contains(&value),
+            Setting::Profile => value <= u8::MAX as u16,
+            Setting::OsMode => OsMode::get(value).is_some(),
+            // the rp2040's watchdog counts to ~8s, halved by erratum rp2040-e1.
+            Setting::WatchdogMs => (100..=4_000).contains(&value),
+            Setting::DebounceMode | Setting::ModifierDebounceMode => {
+                DebounceMode::get(value).is_some()
+            }
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|setting| *setting == self)
+            .unwrap()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum OsMode {
+    Mac = 0x00,
+    Windows = 0x01,
+    Linux = 0x02,
+}
+
+impl OsMode {
+    pub fn get(value: u16) -> Option<OsMode> {
+        match value {
+            0x00 => Some(OsMode::Mac),
+            0x01 => Some(OsMode::Windows),
+            0x02 => Some(OsMode::Linux),
+            _ => None,
+        }
+    }
+}
+
+/// where `GET_SETTING`/`SET_SETTING` read and persist settings, see
+/// `config::protocol`.
+pub trait SettingsStore {
+    fn get(&self, setting: Setting) -> u16;
+    /// whether the value was accepted and persisted.
+    fn set(&mut self, setting: Setting, value: u16) -> bool;
+}
+
+/// the two sectors the log alternates between.
+pub trait SettingsFlash {
+    /// the `SETTINGS_SECTOR_LEN` bytes of sector 0 or 1.
+    fn sector(&self, index: usize) -> &[u8];
+    fn erase(&mut self, index: usize);
+    /// program `bytes` at `offset` into a sector, flash only clears bits so
+    /// this is only ever done over erased bytes.
+    fn program(&mut self, index: usize, offset: usize, bytes: &[u8]);
+}
+
+pub struct SettingsLog<F: SettingsFlash> {
+    flash: F,
+    /// the sector holding the latest header and its sequence, `None` until
+    /// the first setting is stored.
+    active: Option<(usize, u32)>,
+    /// the next free slot of the active sector.
+    next: usize,
+    values: [u16; Setting::ALL.len()],
+}
+
+impl<F: SettingsFlash> SettingsLog<F> {
+    /// replay the log of the newest sector, defaults for what it lacks.
+    pub fn load(flash: F) -> Self {
+        let active = match (header(flash.sector(0)), header(flash.sector(1))) {
+            (Some(first), Some(second)) if second > first => Some((1, second)),
+            (Some(first), _) => Some((0, first)),
+            (None, Some(second)) => Some((1, second)),
+            (None, None) => None,
+        };
+
+        let mut log = Self {
+            flash,
+            active,
+            next: LOG_SLOTS,
+            values: Setting::ALL.map(Setting::default_value),
+        };
+        if let Some((index, _)) = active {
+            let sector = log.flash.sector(index);
+            let mut next = HEADER_SLOTS;
+            for slot in HEADER_SLOTS..LOG_SLOTS {
+                let bytes = &sector[slot * LOG_SLOT_LEN..(slot + 1) * LOG_SLOT_LEN];
+                if bytes.iter().all(|byte| *byte == 0xFF) {
+                    continue;
+                }
+                next = slot + 1;
+                if let Some((setting, value)) = record(bytes) {
+                    log.values[setting.index()] = value;
+                }
+            }
+            log.next = next;
+        }
+        log
+    }
+
+    /// rewrite every current value to the other sector, the header last.
+    /// whether the new header reads back.
+    fn compact(&mut self) -> bool {
+        let (target, sequence) = match self.active {
+            Some((index, sequence)) => (1 - index, sequence.wrapping_add(1)),
+            None => (0, 1),
+        };
+        self.flash.erase(target);
+
+        let mut slot = HEADER_SLOTS;
+        for setting in Setting::ALL {
+            let value = self.values[setting.index()];
+            self.flash
+                .program(target, slot * LOG_SLOT_LEN, &encode_record(setting, value));
+            slot += 1;
+        }
+
+        let mut bytes = [0u8; HEADER_SLOTS * LOG_SLOT_LEN];
+        bytes[..4].copy_from_slice(&SETTINGS_MAGIC);
+        bytes[4..8].copy_from_slice(&sequence.to_le_bytes());
+        let crc = crc32(&bytes[..8]);
+        bytes[8..12].copy_from_slice(&crc.to_le_bytes());
+        self.flash.program(target, 0, &bytes);
+
+        if header(self.flash.sector(target)) != Some(sequence) {
+            error!("unable to compact settings into sector {}", target);
+            return false;
+        }
+        info!("compacted settings into sector {}", target);
+        self.active = Some((target, sequence));
+        self.next = slot;
+        true
+    }
+}
+
+impl<F: SettingsFlash> SettingsStore for SettingsLog<F> {
+    fn get(&self, setting: Setting) -> u16 {
+        self.values[setting.index()]
+    }
+
+    fn set(&mut self, setting: Setting, value: u16) -> bool {
+        if !setting.accepts(value) {
+            return false;
+        }
+        let previous = self.get(setting);
+        if previous == value {
+            return true;
+        }
+        self.values[setting.index()] = value;
+
+        let stored = match self.active {
+            Some((index, _)) if self.next < LOG_SLOTS => {
+                let offset = self.next * LOG_SLOT_LEN;
+                let bytes = encode_record(setting, value);
+                self.flash.program(index, offset, &bytes);
+                // a slot written badly is skipped on load all the same.
+                self.next += 1;
+                self.flash.sector(index)[offset..offset + LOG_SLOT_LEN] == bytes
+            }
+            _ => self.compact(),
+        };
+        if !stored {
+            self.values[setting.index()] = previous;
+        }
+        stored
+    }
+}
+
+/// the sequence of a sector with a valid header.
+fn header(sector: &[u8]) -> Option<u32> {
+    let header = sector.get(..HEADER_SLOTS * LOG_SLOT_LEN)?;
+    let crc = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    if header[..4] != SETTINGS_MAGIC || crc32(&header[..8]) != crc {
+        return None;
+    }
+    Some(u32::from_le_bytes(header[4..8].try_into().unwrap()))
+}
+
+fn record(bytes: &[u8]) -> Option<(Setting, u16)> {
+    let crc = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if crc32(&bytes[..4]) != crc {
+        return None;
+    }
+    let setting = Setting::get(bytes[0])?;
+    let value = u16::from_le_bytes([bytes[2], bytes[3]]);
+    setting.accepts(value).then_some((setting, value))
+}
+
+fn encode_record(setting: Setting, value: u16) -> [u8; LOG_SLOT_LEN] {
+    let mut bytes = [0u8; LOG_SLOT_LEN];
+    bytes[0] = setting as u8;
+    bytes[2..4].copy_from_slice(&value.to_le_bytes());
+    let crc = crc32(&bytes[..4]);
+    bytes[4..].copy_from_slice(&crc.to_le_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// two sectors of ram that, like flash, only clear bits when programmed.
+    #[derive(Clone)]
+    struct MemFlash {
+        sectors: [[u8; SETTINGS_SECTOR_LEN]; 2],
+        /// the bytes that may still be programmed before the power goes,
+        /// `None` for no power loss.
+        power: Option<usize>,
+    }
+
+    impl MemFlash {
+        fn erased() -> Self {
+            Self {
+                sectors: [[0xFF; SETTINGS_SECTOR_LEN]; 2],
+                power: None,
+            }
+        }
+    }
+
+    impl SettingsFlash for MemFlash {
+        fn sector(&self, index: usize) -> &[u8] {
+            &self.sectors[index]
+        }
+
+        fn erase(&mut self, index: usize) {
+            self.sectors[index] = [0xFF; SETTINGS_SECTOR_LEN];
+        }
+
+        fn program(&mut self, index: usize, offset: usize, bytes: &[u8]) {
+            let len = self
+                .power
+                .map_or(bytes.len(), |power| power.min(bytes.len()));
+            if let Some(power) = self.power.as_mut() {
+                *power -= len;
+            }
+            for (byte, new) in self.sectors[index][offset..].iter_mut().zip(&bytes[..len]) {
+                *byte &= new;
+            }
+        }
+    }
+
+    /// a power cycle, the log as the next boot finds it.
+    fn reboot(log: SettingsLog<MemFlash>) -> SettingsLog<MemFlash> {
+        let mut flash = log.flash;
+        flash.power = None;
+        SettingsLog::load(flash)
+    }
+
+    /// `count` changes of the release window, each one appending a record.
+    fn churn(log: &mut SettingsLog<MemFlash>, count: usize) {
+        for i in 0..count {
+            let value = 2 + (i % 2) as u16;
+            assert!(log.set(Setting::DebounceReleaseTicks, value));
+        }
+    }
+
+    // the first change compacts the defaults and itself into sector 0.
+    const FIRST_FREE: usize = HEADER_SLOTS + Setting::ALL.len();
+
+    #[test]
+    fn erased_flash_loads_defaults() {
+        let log = SettingsLog::load(MemFlash::erased());
+        assert_eq!(log.active, None);
+        for setting in Setting::ALL {
+            assert_eq!(log.get(setting), setting.default_value());
+        }
+    }
+
+    #[test]
+    fn latest_record_wins() {
+        let mut log = SettingsLog::load(MemFlash::erased());
+        assert!(log.set(Setting::ScanRateMs, 10));
+        assert!(log.set(Setting::DebounceReleaseTicks, 3));
+        assert!(log.set(Setting::ScanRateMs, 20));
+        assert!(log.set(Setting::DebounceReleaseTicks, 4));
+        assert!(!log.set(Setting::ScanRateMs, 0));
+
+        let log = reboot(log);
+        assert_eq!(log.active, Some((0, 1)));
+        assert_eq!(log.next, FIRST_FREE + 3);
+        assert_eq!(log.get(Setting::ScanRateMs), 20);
+        assert_eq!(log.get(Setting::DebounceReleaseTicks), 4);
+        assert_eq!(log.get(Setting::Profile), Setting::Profile.default_value());
+    }
+
+    #[test]
+    fn torn_record_is_skipped() {
+        let mut log = SettingsLog::load(MemFlash::erased());
+        assert!(log.set(Setting::Profile, 3));
+
+        log.flash.power = Some(LOG_SLOT_LEN / 2);
+        assert!(!log.set(Setting::Profile, 7));
+        assert_eq!(log.get(Setting::Profile), 3);
+
+        let mut log = reboot(log);
+        assert_eq!(log.get(Setting::Profile), 3);
+        // the torn slot isn't reused, the next record goes after it.
+        assert_eq!(log.next, FIRST_FREE + 1);
+        assert!(log.set(Setting::Profile, 8));
+        assert_eq!(reboot(log).get(Setting::Profile), 8);
+    }
+
+    #[test]
+    fn torn_header_keeps_the_previous_sector() {
+        let mut log = SettingsLog::load(MemFlash::erased());
+        assert!(log.set(Setting::Profile, 5));
+        churn(&mut log, LOG_SLOTS - FIRST_FREE);
+        assert_eq!(log.next, LOG_SLOTS);
+        let release_ticks = log.get(Setting::DebounceReleaseTicks);
+
+        // every record makes it into sector 1, the power goes mid header.
+        log.flash.power = Some(Setting::ALL.len() * LOG_SLOT_LEN + 6);
+        assert!(!log.set(Setting::Profile, 6));
+        assert_eq!(log.get(Setting::Profile), 5);
+        assert_eq!(header(log.flash.sector(1)), None);
+
+        let mut log = reboot(log);
+        assert_eq!(log.active, Some((0, 1)));
+        assert_eq!(log.get(Setting::Profile), 5);
+        assert_eq!(log.get(Setting::DebounceReleaseTicks), release_ticks);
+
+        // the next change compacts over the torn sector.
+        assert!(log.set(Setting::Profile, 6));
+        let log = reboot(log);
+        assert_eq!(log.active, Some((1, 2)));
+        assert_eq!(log.get(Setting::Profile), 6);
+        assert_eq!(log.get(Setting::DebounceReleaseTicks), release_ticks);
+    }
+
+    #[test]
+    fn full_sectors_roll_over() {
+        let mut log = SettingsLog::load(MemFlash::erased());
+        assert!(log.set(Setting::WatchdogMs, 2_000));
+        churn(&mut log, LOG_SLOTS - FIRST_FREE);
+        assert_eq!(log.active, Some((0, 1)));
+
+        // sector 0 is full, the next change compacts into sector 1.
+        assert!(log.set(Setting::ScanRateMs, 8));
+        assert_eq!(log.active, Some((1, 2)));
+        assert_eq!(log.next, FIRST_FREE);
+
+        // and once sector 1 fills, back into sector 0.
+        churn(&mut log, LOG_SLOTS - FIRST_FREE);
+        assert!(log.set(Setting::ScanRateMs, 9));
+        assert_eq!(log.active, Some((0, 3)));
+
+        let log = reboot(log);
+        assert_eq!(log.active, Some((0, 3)));
+        assert_eq!(log.get(Setting::WatchdogMs), 2_000);
+        assert_eq!(log.get(Setting::ScanRateMs), 9);
+        assert_eq!(log.get(Setting::DebounceReleaseTicks), 3);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::kb::input::{Modifiers, KEY_ASCII};
+use crate::kb::kbmap::KeyboardMapEntrant;
+
+/// a character scan code as decoded from the matrix, `(column * 16) + row`.
+/// the a2pi serial protocol additionally flags key down with the high bit.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScanCode(pub u8);
+
+impl ScanCode {
+    /// the scan code of a layer's bare modifier entry, i.e. no character held.
+    pub const NONE: ScanCode = ScanCode(0x00);
+
+    pub fn is_none(self) -> bool {
+        self == Self::NONE
+    }
+
+    /// strip the key down flag leaving the keymap index.
+    pub fn ascii(self) -> ScanCode {
+        ScanCode(self.0 & KEY_ASCII)
+    }
+
+    pub fn is_key_down(self) -> bool {
+        self.0 & !KEY_ASCII != 0
+    }
+}
+
+impl From<u8> for ScanCode {
+    fn from(value: u8) -> Self {
+        ScanCode(value)
+    }
+}
+
+impl From<ScanCode> for u8 {
+    fn from(value: ScanCode) -> Self {
+        value.0
+    }
+}
+
+/// the modifier chord a key is resolved on - the sum of the held modifier
+/// scan codes, see `KeyState::handle_modifier_event`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayerMask(pub u8);
+
+impl LayerMask {
+    pub const BARE: LayerMask = LayerMask(0x00);
+
+    pub fn is_bare(self) -> bool {
+        self == Self::BARE
+    }
+}
+
+impl From<u8> for LayerMask {
+    fn from(value: u8) -> Self {
+        LayerMask(value)
+    }
+}
+
+impl From<LayerMask> for u8 {
+    fn from(value: LayerMask) -> Self {
+        value.0
+    }
+}
+
+impl From<Modifiers> for LayerMask {
+    fn from(value: Modifiers) -> Self {
+        LayerMask(value.into())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Press,
+    Release,
+}
+
+/// a scan code resolved against the keymap: the layer it was found on and
+/// the usb hid entrants it renders to.
+#[derive(Clone)]
+pub struct KeyEvent {
+    pub action: KeyAction,
+    pub layer: LayerMask,
+    pub scan_code: ScanCode,
+    pub usb_hid: &'static [KeyboardMapEntrant],
+}
+
+impl KeyEvent {
+    /// whether both events address the same key, regardless of press/release.
+    pub fn same_key(&self, other: &KeyEvent) -> bool {
+        self.scan_code == other.scan_code
+    }
+}
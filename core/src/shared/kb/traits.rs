@@ -0,0 +1,26 @@
+use super::{KeyEvent, LayerMask, ScanCode};
+
+use crate::kb::decoder::{KeyScan, NUM_COLS, NUM_MODS, NUM_ROWS};
+use crate::kb::input::Modifiers;
+use crate::kb::oracle::KbOracleReports;
+use crate::kb::report::KeyboardReport;
+
+use alloc::vec::Vec;
+
+pub trait KeyboardDriver {
+    fn init() -> Self;
+    /// consume a debounced `KeyScan` - sampled from gpio by the firmware or
+    /// scripted by the simulator - and render the resulting reports.
+    fn process_key_event(
+        &mut self,
+        key_scan: KeyScan<NUM_MODS, NUM_ROWS, NUM_COLS>,
+    ) -> Option<Vec<KbOracleReports>>;
+    fn hid_report(self) -> Vec<KeyboardReport>;
+}
+
+pub trait KeyboardKeyMap {
+    fn find_input(&self, layer: LayerMask, scan_code: ScanCode) -> Option<KeyEvent>;
+    /// the layer a chord resolves on - the chord itself when the keymap defines
+    /// it, otherwise the first of its `Modifiers::fallback`s that is.
+    fn resolve_layer(&self, modifiers: Modifiers) -> LayerMask;
+}
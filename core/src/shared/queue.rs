@@ -0,0 +1,148 @@
+/// a fixed capacity fifo, handing reports from the scan loop (the only
+/// producer) to the usb interrupt (the only consumer).
+///
+/// it holds no lock of its own, the firmware keeps it in a
+/// `critical_section::Mutex` like the rest of its shared state. `push` refuses
+/// rather than overwrites once full so the producer can hold on to the report
+/// and retry, nothing is dropped.
+pub struct ReportQueue<T: Copy, const N: usize> {
+    slots: [Option<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> ReportQueue<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            slots: [None; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// enqueue `report`, handing it back when the queue is full.
+    pub fn push(&mut self, report: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(report);
+        }
+        self.slots[(self.head + self.len) % N] = Some(report);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// the oldest report, left queued until `pop` so a report the bus
+    /// couldn't take yet is retried on the next poll.
+    pub fn peek(&self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.slots[self.head]
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let report = self.slots[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        report
+    }
+}
+
+impl<T: Copy, const N: usize> Default for ReportQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_in_push_order() {
+        let mut queue: ReportQueue<u8, 4> = ReportQueue::new();
+        assert!(queue.is_empty());
+        assert!(queue.peek().is_none());
+        assert!(queue.pop().is_none());
+
+        assert!(queue.push(1).is_ok());
+        assert!(queue.push(2).is_ok());
+        assert!(queue.len() == 2);
+
+        // peeking leaves the report queued.
+        assert!(queue.peek() == Some(1));
+        assert!(queue.peek() == Some(1));
+        assert!(queue.pop() == Some(1));
+        assert!(queue.peek() == Some(2));
+        assert!(queue.pop() == Some(2));
+        assert!(queue.is_empty());
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn wraps_around() {
+        let mut queue: ReportQueue<u8, 4> = ReportQueue::new();
+        for report in 0..3 {
+            assert!(queue.push(report).is_ok());
+        }
+        assert!(queue.pop() == Some(0));
+        assert!(queue.pop() == Some(1));
+
+        // the head is at the third slot, these run past the end.
+        for report in 3..6 {
+            assert!(queue.push(report).is_ok());
+        }
+        assert!(queue.is_full());
+        for report in 2..6 {
+            assert!(queue.pop() == Some(report));
+        }
+        assert!(queue.is_empty());
+
+        // and around again.
+        for report in 0..40 {
+            assert!(queue.push(report).is_ok());
+            assert!(queue.pop() == Some(report));
+        }
+    }
+
+    #[test]
+    fn refuses_once_full() {
+        let mut queue: ReportQueue<u8, 4> = ReportQueue::new();
+        for report in 0..4 {
+            assert!(queue.push(report).is_ok());
+        }
+        assert!(queue.is_full());
+        assert!(queue.len() == queue.capacity());
+
+        // the refused report is handed back and nothing queued is lost.
+        assert!(queue.push(4) == Err(4));
+        assert!(queue.len() == 4);
+        assert!(queue.peek() == Some(0));
+
+        // a slot frees up, the retry goes in behind the rest.
+        assert!(queue.pop() == Some(0));
+        assert!(queue.push(4).is_ok());
+        for report in 1..5 {
+            assert!(queue.pop() == Some(report));
+        }
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+/// crc-32 (ieee 802.3, as used by zip and png) of `bytes`.
+///
+/// bitwise rather than table driven - it only ever runs over a keymap at boot
+/// or when one is written, so the 1K table isn't worth the flash.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
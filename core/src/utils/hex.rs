@@ -8,7 +8,3 @@ use hex_display::HexDisplayExt;
 pub fn u8_to_hex_string(number: u8) -> String {
     format!("0x{}", &[number].hex())
 }
-
-pub fn decode_hex(s: &str) -> u8 {
-    u8::from_str_radix(s.trim_start_matches("0x"), 16).unwrap()
-}
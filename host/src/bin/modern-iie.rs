@@ -0,0 +1,243 @@
+//! host-side provisioning for the modernIIe over its configuration interface.
+//!
+//! ```sh
+//! cargo run -p modern_iie_host --bin modern-iie \
+//!     --target x86_64-unknown-linux-gnu -- [--vid <hex>] [--pid <hex>] <command>
+//! ```
+//!
+//! commands:
+//!
+//! - `layers` lists every layer of the active keymap with its key count.
+//! - `dump [<file>]` writes the active keymap as json, to stdout without a file.
+//! - `upload <file> [--reboot]` replaces the keymap with a json file in the
+//!   format `dump` writes and persists it, `--reboot` switches to it right away.
+//! - `diagnostics` prints the firmware and protocol versions, why the device
+//!   last reset, its uptime and scan overruns, keymap totals and the log of a
+//!   crash before the last boot.
+//! - `settings [<name> <value>]` lists the persistent settings, or sets one to
+//!   take effect on the next boot.
+//!
+//! the json keymap mirrors `core/keymap.toml`, so a layer is
+//! `{ "mask": 64, "keys": [{ "scan_code": 32, "hid": ["Keyboard::A"] }] }`.
+//! every command exits non-zero on failure so it can be scripted.
+
+use std::{env, fs, process};
+
+use modern_iie_core::config::protocol::CONFIG_PROTOCOL_VERSION;
+use modern_iie_core::crash::{CrashReason, CrashReport};
+use modern_iie_core::settings::Setting;
+use modern_iie_core::shared::kb::LayerMask;
+use modern_iie_host::drivers::config::{
+    chord_name, ConfigDevice, ConfigError, DEFAULT_PID, DEFAULT_VID,
+};
+use modern_iie_host::drivers::kb::kbmap::DeviceKeyMap;
+
+const USAGE: &str = "usage: modern-iie [--vid <hex>] [--pid <hex>] \
+                     <layers | dump [<file>] | upload <file> [--reboot] | diagnostics \
+                     | settings [<name> <value>]>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    process::exit(2);
+}
+
+fn parse_id(flag: &str, id: Option<&String>) -> u16 {
+    id.and_then(|id| u16::from_str_radix(id.trim_start_matches("0x"), 16).ok())
+        .unwrap_or_else(|| {
+            eprintln!("{flag} expects a hex usb id");
+            process::exit(2);
+        })
+}
+
+fn layers(device: &ConfigDevice) -> Result<(), ConfigError> {
+    for index in 0..device.layer_count()? {
+        let (mask, scan_codes) = device.layer(index)?;
+        println!(
+            "{index:>3}: {:#04x} {:<24} {:>3} keys",
+            mask.0,
+            chord_name(mask),
+            scan_codes.len()
+        );
+    }
+    Ok(())
+}
+
+fn dump(device: &ConfigDevice, path: Option<&String>) -> Result<(), String> {
+    let layers = device.keymap().map_err(|e| e.to_string())?;
+    let keymap = serde_json::to_string_pretty(&DeviceKeyMap::from_layers(&layers))
+        .map_err(|e| e.to_string())?;
+
+    match path {
+        Some(path) => fs::write(path, keymap).map_err(|e| format!("unable to write {path}: {e}")),
+        None => {
+            println!("{keymap}");
+            Ok(())
+        }
+    }
+}
+
+/// clear whatever the file doesn't map, set everything it does, then commit.
+fn upload(device: &ConfigDevice, path: &str, reboot: bool) -> Result<(), String> {
+    let layers = DeviceKeyMap::open(path)?.into_layers()?;
+    let active = device.keymap().map_err(|e| e.to_string())?;
+
+    for (mask, keys) in active.iter() {
+        let kept = layers
+            .iter()
+            .find(|(layer, _)| layer == mask)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[]);
+        for (scan_code, ..) in keys.iter() {
+            if !kept.iter().any(|key| key.0 == *scan_code) {
+                device
+                    .clear_key(*mask, *scan_code)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    for (mask, keys) in layers.iter() {
+        for key in keys.iter() {
+            device.set_key(*mask, key).map_err(|e| e.to_string())?;
+        }
+    }
+
+    device.commit().map_err(|e| e.to_string())?;
+    println!(
+        "uploaded {} layers, {} keys",
+        layers.len(),
+        layers.iter().map(|(_, keys)| keys.len()).sum::<usize>()
+    );
+
+    if reboot {
+        device.reboot().map_err(|e| e.to_string())?;
+        println!("rebooting into the new keymap");
+    }
+    Ok(())
+}
+
+fn diagnostics(device: &ConfigDevice) -> Result<(), ConfigError> {
+    let (major, minor, patch, protocol) = device.version()?;
+    println!("firmware:  {major}.{minor}.{patch}");
+    println!("protocol:  {protocol} (host speaks {CONFIG_PROTOCOL_VERSION})");
+    println!(
+        "serial:    {}",
+        device.serial_number.as_deref().unwrap_or("unknown")
+    );
+
+    let health = device.health()?;
+    println!("reset:     {}", health.reset_reason.name());
+    println!("uptime:    {}s", health.uptime_ms / 1000);
+    println!("overruns:  {} scans", health.scan_overruns);
+
+    let mut keys = 0;
+    let layer_count = device.layer_count()?;
+    for index in 0..layer_count {
+        keys += device.layer(index)?.1.len();
+    }
+    println!("keymap:    {layer_count} layers, {keys} keys");
+
+    match device.crash_log()?.as_deref().map(CrashReport::decode) {
+        None => println!("crash:     none since the last boot"),
+        Some(None) => println!("crash:     unreadable log"),
+        Some(Some(report)) => {
+            let reason = match report.reason {
+                CrashReason::Panic => "panic",
+                CrashReason::HardFault => "hard fault",
+            };
+            println!("crash:     {reason}: {}", report.message);
+            for event in report.events.iter() {
+                let keys = event
+                    .keys
+                    .iter()
+                    .take(event.key_count as usize)
+                    .map(|key| format!("{key:#04x}"))
+                    .collect::<Vec<_>>();
+                println!(
+                    "           {:<24} {}",
+                    chord_name(LayerMask(event.modifiers)),
+                    keys.join(" ")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn settings(device: &ConfigDevice, assignment: &[&String]) -> Result<(), String> {
+    if let [name, value] = assignment {
+        let setting = Setting::from_name(name).ok_or_else(|| format!("no setting {name}"))?;
+        let value = value
+            .parse::<u16>()
+            .ok()
+            .filter(|value| setting.accepts(*value))
+            .ok_or_else(|| format!("{value} is out of range for {name}"))?;
+        device
+            .set_setting(setting, value)
+            .map_err(|e| e.to_string())?;
+        println!("{name} = {value} from the next boot");
+        return Ok(());
+    }
+
+    for setting in Setting::ALL {
+        let value = device.setting(setting).map_err(|e| e.to_string())?;
+        println!("{:<16} {value}", setting.name());
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut vid = DEFAULT_VID;
+    let mut pid = DEFAULT_PID;
+    let mut reboot = false;
+    let mut positional = Vec::new();
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--vid" => vid = parse_id("--vid", args_iter.next()),
+            "--pid" => pid = parse_id("--pid", args_iter.next()),
+            "--reboot" => reboot = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let Some((command, rest)) = positional.split_first() else {
+        usage();
+    };
+    if !matches!(
+        command.as_str(),
+        "layers" | "dump" | "upload" | "diagnostics" | "settings"
+    ) {
+        usage();
+    }
+    if command.as_str() == "upload" && rest.is_empty() {
+        usage();
+    }
+    if command.as_str() == "settings" && !matches!(rest.len(), 0 | 2) {
+        usage();
+    }
+
+    let device = ConfigDevice::open(vid, pid).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+
+    let result = match command.as_str() {
+        "layers" => layers(&device).map_err(|e| e.to_string()),
+        "dump" => dump(&device, rest.first().copied()),
+        "upload" => upload(&device, rest[0], reboot),
+        "settings" => settings(&device, rest),
+        _ => diagnostics(&device).map_err(|e| e.to_string()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,174 @@
+//! host-runnable simulator for the scan -> `KbOracleReports` pipeline.
+//!
+//! drives `KbDriver::process_key_event` from a scripted sequence of matrix and
+//! modifier states so layer/macro bugs can be reproduced without an rp2040 or
+//! an apple iie attached.
+//!
+//! ```sh
+//! cargo run -p modern_iie_host --bin simulator \
+//!     --target x86_64-unknown-linux-gnu -- script.txt [--debounce <ticks>]
+//! ```
+//!
+//! a script is read line by line where every line is one scan tick:
+//!
+//! ```text
+//! # open apple + A, held for 3 ticks
+//! open 0x20 *3
+//! # release everything
+//! -
+//! ```
+//!
+//! tokens are either a modifier line (`closed`, `open`, `control`, `reset`,
+//! `shift`), a matrix scan code in hex (`(column * 16) + row`, as used by the
+//! keymap), `*<n>` to repeat the tick `n` times or `-` for an idle tick.
+
+use std::{env, fs, process};
+
+use modern_iie_core::kb::{
+    decoder::{Debounce, KeyScan, NUM_COLS, NUM_MODS, NUM_ROWS},
+    driver::KbDriver,
+    oracle::KbOracleReports,
+};
+use modern_iie_core::shared::kb::KeyboardDriver;
+
+const DEFAULT_DEBOUNCE_TICKS: u8 = 1;
+
+struct Tick {
+    line: usize,
+    repeat: usize,
+    mods: [bool; NUM_MODS],
+    matrix: [[bool; NUM_ROWS]; NUM_COLS],
+}
+
+/// index of a modifier line in the raw modifier sample, see `KeyScan::into_decoder`.
+fn modifier_index(token: &str) -> Option<usize> {
+    match token {
+        "closed" => Some(0),
+        "open" => Some(1),
+        "control" => Some(2),
+        "reset" => Some(3),
+        "shift" => Some(4),
+        _ => None,
+    }
+}
+
+fn parse_tick(line: usize, source: &str) -> Result<Option<Tick>, String> {
+    let source = source.split('#').next().unwrap_or("").trim();
+    if source.is_empty() {
+        return Ok(None);
+    }
+
+    let mut tick = Tick {
+        line,
+        repeat: 1,
+        mods: [false; NUM_MODS],
+        matrix: [[false; NUM_ROWS]; NUM_COLS],
+    };
+
+    for token in source.split_whitespace() {
+        if token == "-" {
+            continue;
+        }
+        if let Some(repeat) = token.strip_prefix('*') {
+            tick.repeat = repeat
+                .parse()
+                .map_err(|_| format!("line {line}: invalid repeat `{token}`"))?;
+            continue;
+        }
+        if let Some(modifier) = modifier_index(token) {
+            tick.mods[modifier] = true;
+            continue;
+        }
+
+        let scan_code = u8::from_str_radix(token.trim_start_matches("0x"), 16)
+            .map_err(|_| format!("line {line}: unknown token `{token}`"))?;
+        let (col, row) = ((scan_code >> 4) as usize, (scan_code & 0x0f) as usize);
+        if col >= NUM_COLS || row >= NUM_ROWS {
+            return Err(format!(
+                "line {line}: scan code {scan_code:#04x} is outside of the {NUM_COLS}x{NUM_ROWS} matrix"
+            ));
+        }
+        tick.matrix[col][row] = true;
+    }
+
+    Ok(Some(tick))
+}
+
+fn print_reports(tick: usize, line: usize, reports: Option<Vec<KbOracleReports>>) {
+    let rendered = reports
+        .unwrap_or_default()
+        .iter()
+        .map(|report| match report {
+            KbOracleReports::Keyboard(k) => format!(
+                "keyboard(modifier={:#04x} keycodes={:02x?} boot={:02x?})",
+                k.modifier,
+                k.keycodes().collect::<Vec<u8>>(),
+                k.to_boot().keycodes
+            ),
+            KbOracleReports::Consumer(c) => format!("consumer(usage_id={:#06x})", c.usage_id),
+            KbOracleReports::System(s) => format!("system(usage_id={:#04x})", s.usage_id),
+        })
+        .collect::<Vec<String>>();
+
+    println!("{tick:>5} (line {line:>3}): {}", rendered.join(" "));
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut script_path = None;
+    let mut debounce_ticks = DEFAULT_DEBOUNCE_TICKS;
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--debounce" => {
+                debounce_ticks = args_iter
+                    .next()
+                    .and_then(|ticks| ticks.parse().ok())
+                    .unwrap_or_else(|| {
+                        eprintln!("--debounce expects a number of ticks");
+                        process::exit(2);
+                    })
+            }
+            path => script_path = Some(path.to_string()),
+        }
+    }
+
+    let Some(script_path) = script_path else {
+        eprintln!("usage: simulator <script> [--debounce <ticks>]");
+        process::exit(2);
+    };
+
+    let script = fs::read_to_string(&script_path).unwrap_or_else(|e| {
+        eprintln!("unable to read {script_path}: {e}");
+        process::exit(1);
+    });
+
+    let mut ticks = Vec::new();
+    for (idx, line) in script.lines().enumerate() {
+        match parse_tick(idx + 1, line) {
+            Ok(Some(tick)) => ticks.push(tick),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("{script_path}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut a2pi = KbDriver::init();
+    let mut debounce: Debounce<NUM_MODS, NUM_ROWS, NUM_COLS> = Debounce::new(debounce_ticks);
+
+    // as the firmware passes scans on, with ghosted keys held back.
+    let mut previous = KeyScan::released();
+    let mut tick_count = 0usize;
+    for tick in ticks.iter() {
+        for _ in 0..tick.repeat {
+            let mut key_scan = KeyScan::from_raw(&tick.mods, &tick.matrix, &mut debounce);
+            key_scan.suppress_ghosts(&previous);
+            previous = key_scan;
+            print_reports(tick_count, tick.line, a2pi.process_key_event(key_scan));
+            tick_count += 1;
+        }
+    }
+}
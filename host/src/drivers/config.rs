@@ -0,0 +1,273 @@
+//! the host end of the configuration channel, see
+//! `modern_iie_core::config::protocol` for the wire format.
+
+use std::fmt;
+
+use hidapi::{HidApi, HidDevice, HidError};
+use modern_iie_core::config::protocol::*;
+use modern_iie_core::config::CONFIG_MAX_USAGES;
+use modern_iie_core::health::{Health, ResetReason};
+use modern_iie_core::kb::input::Modifiers;
+use modern_iie_core::kb::kbmap::storage::{self, StoredKey, StoredLayer};
+use modern_iie_core::settings::Setting;
+use modern_iie_core::shared::kb::LayerMask;
+
+/// the ids the firmware enumerates with unless built with others, see
+/// `build.rs`.
+pub const DEFAULT_VID: u16 = 0x1209;
+pub const DEFAULT_PID: u16 = 0x0001;
+
+// how long to wait on a response before giving up on the device.
+const RESPONSE_TIMEOUT_MS: i32 = 1000;
+
+pub enum ConfigError {
+    Hid(HidError),
+    /// no configuration interface with the given ids is attached.
+    NotFound(u16, u16),
+    /// no response within `RESPONSE_TIMEOUT_MS`.
+    Timeout(u8),
+    /// the response echoed a different command, or an unknown status.
+    Unexpected(u8),
+    /// the device answered with a failure.
+    Status(u8, Status),
+}
+
+impl From<HidError> for ConfigError {
+    fn from(err: HidError) -> Self {
+        ConfigError::Hid(err)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Hid(err) => write!(f, "hid: {}", err),
+            ConfigError::NotFound(vid, pid) => write!(
+                f,
+                "no configuration interface found for {:04x}:{:04x}",
+                vid, pid
+            ),
+            ConfigError::Timeout(command) => write!(f, "command {:#04x} timed out", command),
+            ConfigError::Unexpected(command) => {
+                write!(f, "command {:#04x} got an unexpected response", command)
+            }
+            ConfigError::Status(command, status) => {
+                let reason = match status {
+                    Status::Ok => "ok",
+                    Status::UnknownCommand => "unknown command",
+                    Status::InvalidArgument => "invalid argument",
+                    Status::NotFound => "not found",
+                    Status::StorageFailed => "storage failed",
+                };
+                write!(f, "command {:#04x} failed: {}", command, reason)
+            }
+        }
+    }
+}
+
+/// the firmware's `GET_VERSION`: `(major, minor, patch, protocol version)`.
+pub type FirmwareVersion = (u8, u8, u8, u8);
+
+pub struct ConfigDevice {
+    device: HidDevice,
+    pub serial_number: Option<String>,
+}
+
+impl ConfigDevice {
+    /// open the configuration interface of the first device matching `vid:pid`.
+    ///
+    /// the keyboard interface of the same device is skipped by usage page, so
+    /// this never grabs the keys.
+    pub fn open(vid: u16, pid: u16) -> Result<Self, ConfigError> {
+        let api = HidApi::new()?;
+        let info = api
+            .device_list()
+            .find(|info| {
+                info.vendor_id() == vid
+                    && info.product_id() == pid
+                    && info.usage_page() == CONFIG_USAGE_PAGE
+                    && info.usage() == CONFIG_USAGE as u16
+            })
+            .ok_or(ConfigError::NotFound(vid, pid))?;
+
+        Ok(Self {
+            device: info.open_device(&api)?,
+            serial_number: info.serial_number().map(|serial| serial.to_string()),
+        })
+    }
+
+    /// send one request and wait for its response data.
+    pub fn request(
+        &self,
+        command: u8,
+        args: &[u8],
+    ) -> Result<[u8; CONFIG_REPORT_LEN - CONFIG_RESPONSE_HEADER_LEN], ConfigError> {
+        // the interface has no report ids, hidapi still wants a leading zero.
+        let mut report = [0u8; CONFIG_REPORT_LEN + 1];
+        report[1] = command;
+        report[2..2 + args.len()].copy_from_slice(args);
+        self.device.write(&report)?;
+
+        let mut response = [0u8; CONFIG_REPORT_LEN];
+        if self
+            .device
+            .read_timeout(&mut response, RESPONSE_TIMEOUT_MS)?
+            == 0
+        {
+            return Err(ConfigError::Timeout(command));
+        }
+        if response[0] != command {
+            return Err(ConfigError::Unexpected(command));
+        }
+        match Status::get(response[1]) {
+            Some(Status::Ok) => {}
+            Some(status) => return Err(ConfigError::Status(command, status)),
+            None => return Err(ConfigError::Unexpected(command)),
+        }
+
+        let mut data = [0u8; CONFIG_REPORT_LEN - CONFIG_RESPONSE_HEADER_LEN];
+        data.copy_from_slice(&response[CONFIG_RESPONSE_HEADER_LEN..]);
+        Ok(data)
+    }
+
+    pub fn version(&self) -> Result<FirmwareVersion, ConfigError> {
+        let data = self.request(GET_VERSION, &[])?;
+        Ok((data[0], data[1], data[2], data[3]))
+    }
+
+    pub fn layer_count(&self) -> Result<u8, ConfigError> {
+        Ok(self.request(GET_LAYER_COUNT, &[])?[0])
+    }
+
+    /// the mask and mapped scan codes of the layer at `index`.
+    pub fn layer(&self, index: u8) -> Result<(LayerMask, Vec<u8>), ConfigError> {
+        let mut scan_codes = Vec::new();
+        loop {
+            let data = self.request(GET_LAYER, &[index, scan_codes.len() as u8])?;
+            let (mask, count) = (LayerMask(data[0]), data[1] as usize);
+            let offset = scan_codes.len();
+            scan_codes.extend(data[2..].iter().take(count.saturating_sub(offset)));
+
+            if scan_codes.len() >= count || scan_codes.len() == offset {
+                return Ok((mask, scan_codes));
+            }
+        }
+    }
+
+    pub fn key(&self, mask: LayerMask, scan_code: u8) -> Result<StoredKey, ConfigError> {
+        let data = self.request(GET_KEY, &[mask.0, scan_code])?;
+        let usb_hid = data[3..]
+            .chunks_exact(CONFIG_USAGE_LEN)
+            .take(data[2] as usize)
+            .map(|usage| storage::entrant_of(usage[0], u16::from_le_bytes([usage[1], usage[2]])))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ConfigError::Unexpected(GET_KEY))?;
+
+        Ok((scan_code, data[0], data[1], usb_hid))
+    }
+
+    /// the whole draft keymap, layer by layer.
+    pub fn keymap(&self) -> Result<Vec<StoredLayer>, ConfigError> {
+        (0..self.layer_count()?)
+            .map(|index| {
+                let (mask, scan_codes) = self.layer(index)?;
+                let keys = scan_codes
+                    .into_iter()
+                    .map(|scan_code| self.key(mask, scan_code))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((mask, keys))
+            })
+            .collect()
+    }
+
+    pub fn set_key(&self, mask: LayerMask, key: &StoredKey) -> Result<(), ConfigError> {
+        let (scan_code, key_up, key_down, usb_hid) = key;
+        let usages = usb_hid.len().min(CONFIG_MAX_USAGES);
+
+        let mut args = vec![mask.0, *scan_code, *key_up, *key_down, usages as u8];
+        for entrant in usb_hid.iter().take(usages) {
+            let (page, usage) = storage::usage_of(entrant);
+            args.push(page);
+            args.extend_from_slice(&usage.to_le_bytes());
+        }
+
+        self.request(SET_KEY, &args).map(|_| ())
+    }
+
+    pub fn clear_key(&self, mask: LayerMask, scan_code: u8) -> Result<(), ConfigError> {
+        self.request(CLEAR_KEY, &[mask.0, scan_code]).map(|_| ())
+    }
+
+    /// persist the draft to flash.
+    pub fn commit(&self) -> Result<(), ConfigError> {
+        self.request(COMMIT, &[]).map(|_| ())
+    }
+
+    pub fn setting(&self, setting: Setting) -> Result<u16, ConfigError> {
+        let data = self.request(GET_SETTING, &[setting as u8])?;
+        Ok(u16::from_le_bytes([data[0], data[1]]))
+    }
+
+    /// persist a setting, it takes effect on the next boot.
+    pub fn set_setting(&self, setting: Setting, value: u16) -> Result<(), ConfigError> {
+        let [low, high] = value.to_le_bytes();
+        self.request(SET_SETTING, &[setting as u8, low, high])
+            .map(|_| ())
+    }
+
+    /// the log of a crash before the device's last boot, `None` if it didn't.
+    pub fn crash_log(&self) -> Result<Option<Vec<u8>>, ConfigError> {
+        let mut log = Vec::new();
+        loop {
+            let data = match self.request(GET_CRASH_LOG, &(log.len() as u16).to_le_bytes()) {
+                Err(ConfigError::Status(_, Status::NotFound)) => return Ok(None),
+                data => data?,
+            };
+            let len = u16::from_le_bytes([data[0], data[1]]) as usize;
+            let offset = log.len();
+            log.extend(data[2..].iter().take(len.saturating_sub(offset)));
+
+            if log.len() >= len || log.len() == offset {
+                return Ok(Some(log));
+            }
+        }
+    }
+
+    /// why the device last reset, how long it has been up since and the scan
+    /// periods it overran meanwhile.
+    pub fn health(&self) -> Result<Health, ConfigError> {
+        let data = self.request(GET_HEALTH, &[])?;
+        let reset_reason = ResetReason::get(data[0]).ok_or(ConfigError::Unexpected(GET_HEALTH))?;
+        Ok(Health {
+            reset_reason,
+            uptime_ms: u32::from_le_bytes(data[1..5].try_into().unwrap()),
+            scan_overruns: u32::from_le_bytes(data[5..9].try_into().unwrap()),
+        })
+    }
+
+    /// reset the device into the committed keymap.
+    pub fn reboot(&self) -> Result<(), ConfigError> {
+        self.request(REBOOT, &[]).map(|_| ())
+    }
+}
+
+/// a chord as `open+shift`, `bare` when no modifier is held.
+pub fn chord_name(mask: LayerMask) -> String {
+    let chord = Modifiers::from(mask.0);
+    if chord.is_bare() {
+        return chord.outer_as_string();
+    }
+
+    [
+        Modifiers::OPEN_APPLE,
+        Modifiers::CLOSED_APPLE,
+        Modifiers::CONTROL,
+        Modifiers::SHIFT,
+        Modifiers::RESET,
+    ]
+    .iter()
+    .filter(|modifier| chord.contains(**modifier))
+    .map(|modifier| modifier.outer_as_string())
+    .collect::<Vec<_>>()
+    .join("+")
+}
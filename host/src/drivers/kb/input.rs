@@ -1,9 +1,11 @@
+use modern_iie_core::kb::input::Modifiers as CoreModifiers;
+
 use crate::errors::A2PiError;
 
-use crate::drivers::shared::kb::structs::Key;
+use super::kbmap::Key;
 
-// pub const MOD_FN: u8 = 0x80u8;
-pub const KEY_ASCII: u8 = 0x7Fu8;
+// the serial daemon applies the same scan code semantics as the firmware.
+pub use modern_iie_core::kb::input::KEY_ASCII;
 
 #[derive(Clone)]
 pub enum Modifiers {
@@ -15,12 +17,13 @@ pub enum Modifiers {
 
 impl Modifiers {
     pub fn get(modifier_scan_code: u8) -> Option<Modifiers> {
-        match modifier_scan_code {
-            bare if modifier_scan_code == 0x0u8 => Some(Modifiers::Bare(bare)),
-            open if modifier_scan_code == 0x40u8 => Some(Modifiers::OpenApple(open)),
-            closed if modifier_scan_code == 0x80u8 => Some(Modifiers::ClosedApple(closed)),
-            open_closed if modifier_scan_code == 0xC0u8 => {
-                Some(Modifiers::OpenClosedApple(open_closed))
+        // the a2pi serial protocol only reports the apple keys.
+        match CoreModifiers::get(modifier_scan_code)? {
+            CoreModifiers::BARE => Some(Modifiers::Bare(modifier_scan_code)),
+            CoreModifiers::OPEN_APPLE => Some(Modifiers::OpenApple(modifier_scan_code)),
+            CoreModifiers::CLOSED_APPLE => Some(Modifiers::ClosedApple(modifier_scan_code)),
+            CoreModifiers::OPEN_CLOSED_APPLE => {
+                Some(Modifiers::OpenClosedApple(modifier_scan_code))
             }
             _ => None,
         }
@@ -0,0 +1,187 @@
+use std::{collections::HashMap, fs, fs::File, io::Read};
+
+use modern_iie_core::config::CONFIG_MAX_USAGES;
+use modern_iie_core::kb::input::Modifiers;
+use modern_iie_core::kb::kbmap::storage::{
+    entrant_of, StoredKey, StoredLayer, PAGE_CONSUMER, PAGE_DESKTOP, PAGE_KEYBOARD,
+};
+use modern_iie_core::kb::kbmap::{KeyboardMapEntrant, LAYOUT_KEYS};
+use modern_iie_core::shared::kb::LayerMask;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_value, Map, Value};
+
+#[derive(Clone, Deserialize)]
+pub struct Key {
+    pub key: String,
+    pub action: String,
+}
+
+#[derive(Clone)]
+pub struct KeyMap {
+    pub layout: HashMap<String, Key>,
+}
+
+impl KeyMap {
+    fn from_serde_map(obj: Map<String, Value>) -> HashMap<String, Key> {
+        let mut layout = HashMap::new();
+
+        for (key, value) in obj.iter() {
+            let key_map_key_value = value.clone();
+            let key_map_key: Key = from_value(key_map_key_value).unwrap();
+            layout.insert(key.to_string(), key_map_key);
+        }
+
+        layout
+    }
+    pub fn open(file: Option<String>) -> KeyMap {
+        let mut layout = HashMap::new();
+        if file.is_none() {
+            let mut file = File::open("./a2pi_keymaps/kbmap.json").unwrap();
+            let mut data = String::new();
+            file.read_to_string(&mut data).unwrap();
+
+            let parsed: Map<String, Value> = serde_json::from_str(&data).unwrap();
+            layout = KeyMap::from_serde_map(parsed);
+        }
+        Self { layout }
+    }
+    pub fn find_scan_code(self, scan_code: String) -> Option<Key> {
+        self.layout.get(&scan_code).cloned()
+    }
+}
+
+/// a device keymap as text, the json twin of `core/keymap.toml`. written by
+/// `modern-iie dump` and read back by `modern-iie upload`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeviceKeyMap {
+    pub layer: Vec<DeviceLayer>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeviceLayer {
+    pub mask: u8,
+    pub keys: Vec<DeviceKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeviceKey {
+    pub scan_code: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_up: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_down: Option<u8>,
+    pub hid: Vec<String>,
+}
+
+impl DeviceKeyMap {
+    pub fn open(path: &str) -> Result<DeviceKeyMap, String> {
+        let data = fs::read_to_string(path).map_err(|e| format!("unable to read {path}: {e}"))?;
+        serde_json::from_str(&data).map_err(|e| format!("{path}: {e}"))
+    }
+
+    pub fn from_layers(layers: &[StoredLayer]) -> DeviceKeyMap {
+        let layer = layers
+            .iter()
+            .map(|(mask, keys)| DeviceLayer {
+                mask: mask.0,
+                keys: keys
+                    .iter()
+                    .map(|(scan_code, key_up, key_down, usb_hid)| DeviceKey {
+                        scan_code: *scan_code,
+                        key_up: Some(*key_up).filter(|key_up| key_up != scan_code),
+                        key_down: Some(*key_down).filter(|key_down| key_down != scan_code),
+                        hid: usb_hid.iter().map(usage_name).collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        DeviceKeyMap { layer }
+    }
+
+    /// check the keymap the same way `core/build.rs` does and convert it
+    /// into the form the configuration channel speaks.
+    pub fn into_layers(self) -> Result<Vec<StoredLayer>, String> {
+        let mut layers: Vec<StoredLayer> = Vec::new();
+
+        for layer in self.layer {
+            let mask = Modifiers::get(layer.mask)
+                .map(LayerMask::from)
+                .ok_or_else(|| format!("layer {:#04x} is not a modifier chord", layer.mask))?;
+            if layers.iter().any(|(other, _)| *other == mask) {
+                return Err(format!("layer {:#04x} is defined twice", layer.mask));
+            }
+
+            let mut keys: Vec<StoredKey> = Vec::new();
+            for key in layer.keys {
+                let at = format!("layer {:#04x} key {:#04x}", layer.mask, key.scan_code);
+
+                if key.scan_code as usize >= LAYOUT_KEYS {
+                    return Err(format!("{at}: scan codes must be below {LAYOUT_KEYS:#04x}"));
+                }
+                if keys.iter().any(|other| other.0 == key.scan_code) {
+                    return Err(format!("{at}: is defined twice"));
+                }
+                if key.hid.is_empty() || key.hid.len() > CONFIG_MAX_USAGES {
+                    return Err(format!(
+                        "{at}: `hid` must list between 1 and {CONFIG_MAX_USAGES} usages"
+                    ));
+                }
+
+                let usb_hid = key
+                    .hid
+                    .iter()
+                    .map(|usage| {
+                        usage_of_name(usage).ok_or_else(|| format!("{at}: unknown usage `{usage}`"))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+
+                keys.push((
+                    key.scan_code,
+                    key.key_up.unwrap_or(key.scan_code),
+                    key.key_down.unwrap_or(key.scan_code),
+                    usb_hid,
+                ));
+            }
+
+            keys.sort_by_key(|key| key.0);
+            layers.push((mask, keys));
+        }
+
+        Ok(layers)
+    }
+}
+
+/// an entrant as `keymap.toml` names it, `Keyboard::A`.
+pub fn usage_name(entrant: &KeyboardMapEntrant) -> String {
+    match entrant {
+        KeyboardMapEntrant::Keyboard(keyboard) => format!("Keyboard::{keyboard:?}"),
+        KeyboardMapEntrant::Consumer(consumer) => format!("Consumer::{consumer:?}"),
+        KeyboardMapEntrant::System(system) => format!("System::{system:?}"),
+    }
+}
+
+/// the entrant named `Keyboard::<usage>`, `Consumer::<usage>` or
+/// `System::<usage>`, the usage may also be given as a hex id (`Keyboard::0x04`).
+pub fn usage_of_name(name: &str) -> Option<KeyboardMapEntrant> {
+    let (page, usage) = name.split_once("::")?;
+    let (page, ids) = match page {
+        "Keyboard" => (PAGE_KEYBOARD, 0x00..=0xFF),
+        "Consumer" => (PAGE_CONSUMER, 0x0000..=0xFFFF),
+        "System" => (PAGE_DESKTOP, 0x00..=0xFF),
+        _ => return None,
+    };
+
+    if let Some(id) = usage.strip_prefix("0x") {
+        return u16::from_str_radix(id, 16)
+            .ok()
+            .filter(|id| ids.contains(id))
+            .and_then(|id| entrant_of(page, id));
+    }
+
+    ids.filter_map(|id| entrant_of(page, id))
+        .find(|entrant| usage_name(entrant).split_once("::").map(|(_, name)| name) == Some(usage))
+}
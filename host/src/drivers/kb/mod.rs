@@ -0,0 +1,7 @@
+pub mod driver;
+pub mod events;
+pub mod handshake;
+pub mod input;
+pub mod kbmap;
+pub mod state;
+pub mod vdev;
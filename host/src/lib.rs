@@ -0,0 +1,6 @@
+//! host side tooling: the serial A2Pi driver, the scan pipeline simulator and
+//! the `modern-iie` provisioning cli, all built on `modern_iie_core`.
+
+pub mod drivers;
+pub mod errors;
+pub mod state;
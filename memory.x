@@ -1,6 +1,10 @@
 MEMORY {
     BOOT2 : ORIGIN = 0x10000000, LENGTH = 0x100
-    FLASH : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100
+    FLASH : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100 - 64K - 8K
+    /* the settings log, see `modern_iie_core::settings` */
+    SETTINGS : ORIGIN = 0x10000000 + 2048K - 64K - 8K, LENGTH = 8K
+    /* the stored keymap, see `modern_iie_core::kb::kbmap::storage` */
+    KEYMAP : ORIGIN = 0x10000000 + 2048K - 64K, LENGTH = 64K
     RAM   : ORIGIN = 0x20000000, LENGTH = 256K
 }
 
@@ -13,3 +17,10 @@ SECTIONS {
         KEEP(*(.boot2));
     } > BOOT2
 } INSERT BEFORE .text;
+
+/* the keymap and settings regions are never linked into, only addressed by
+   the firmware */
+__keymap_start = ORIGIN(KEYMAP);
+__keymap_end = ORIGIN(KEYMAP) + LENGTH(KEYMAP);
+__settings_start = ORIGIN(SETTINGS);
+__settings_end = ORIGIN(SETTINGS) + LENGTH(SETTINGS);
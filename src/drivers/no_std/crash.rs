@@ -0,0 +1,66 @@
+//! the panic and hard fault handlers, recording into a `CrashLog` in ram that
+//! the reset leaves alone (`.uninit`, which cortex-m-rt never zeroes) and then
+//! letting the watchdog reset the board rather than freezing it.
+
+use core::mem::MaybeUninit;
+use core::panic::PanicInfo;
+
+use cortex_m_rt::{exception, ExceptionFrame};
+use fugit::ExtU32;
+use modern_iie_core::crash::{CrashLog, CrashReason};
+use rp2040_hal as hal;
+use rp2040_hal::pac;
+
+#[link_section = ".uninit.CRASH_LOG"]
+static mut CRASH_LOG: MaybeUninit<CrashLog> = MaybeUninit::uninit();
+
+/// how long a crash has to be reported on before the board resets.
+const CRASH_RESET_US: u32 = 10_000;
+
+fn crash_log() -> &'static mut CrashLog {
+    // every bit pattern is a `CrashLog`, see its docs.
+    unsafe { CRASH_LOG.assume_init_mut() }
+}
+
+/// the log of the previous boot if it crashed, the log is cleared for this
+/// boot either way. called once, before anything is recorded.
+pub fn take() -> Option<alloc::vec::Vec<u8>> {
+    crash_log().take()
+}
+
+/// note a debounced scan in the log's ring of recent scans.
+pub fn record_scan(modifiers: u8, keys: impl Iterator<Item = u8>) {
+    crash_log().record_scan(modifiers, keys);
+}
+
+fn reset() -> ! {
+    // the watchdog ticks off the clocks `main` set up, and if it never got
+    // that far there's nothing to lose by asking the core to reset.
+    let mut watchdog = hal::Watchdog::new(unsafe { pac::Peripherals::steal().WATCHDOG });
+    watchdog.start(CRASH_RESET_US.micros());
+    // twice the watchdog's timeout at 125MHz, less before clocks are up.
+    cortex_m::asm::delay(CRASH_RESET_US * 250);
+    cortex_m::peripheral::SCB::sys_reset();
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    cortex_m::interrupt::disable();
+    crash_log().record_crash(CrashReason::Panic, format_args!("{}", info));
+    defmt::error!("{}", defmt::Display2Format(info));
+    reset()
+}
+
+#[exception]
+unsafe fn HardFault(frame: &ExceptionFrame) -> ! {
+    crash_log().record_crash(
+        CrashReason::HardFault,
+        format_args!(
+            "hard fault at pc {:#010x}, lr {:#010x}",
+            frame.pc(),
+            frame.lr()
+        ),
+    );
+    defmt::error!("hard fault at pc {:#x}", frame.pc());
+    reset()
+}
@@ -1,21 +1,131 @@
-use alloc::vec::Vec;
-use rp2040_hal::gpio::{Input, Pins};
+//! the key matrix, strobed and sampled by a pio state machine rather than the
+//! core. dma feeds it the column masks and takes its samples into two frame
+//! buffers in turn, so a scan costs the core a copy instead of ~1ms of delays.
+//!
+//! the IIe's lines are spread over the gpio, so the state machine drives and
+//! samples all 32 of them at once: only the columns are handed to the pio, the
+//! rest keep their function and pulls and are merely read.
 
-pub struct DecoderPin {}
+use hal::dma::double_buffer::{Config, ReadNext, Transfer, WriteNext};
+use hal::dma::{Channel, CH0, CH1, CH2, CH3};
+use hal::pac::PIO0;
+use hal::pio::{PIOBuilder, PinDir, Rx, Tx, UninitStateMachine, PIO, PIO0SM0};
+use modern_iie_core::kb::decoder::{NUM_COLS, NUM_MODS, NUM_ROWS};
+use rp2040_hal as hal;
 
-pub struct DecoderMatix {
-    pub pins: Vec<Vec<u64>>,
+/// X0..X7.
+pub const COLUMN_PINS: [u8; NUM_COLS] = [13, 17, 15, 19, 20, 18, 28, 16];
+/// Y0..Y9.
+pub const ROW_PINS: [u8; NUM_ROWS] = [2, 3, 4, 14, 8, 10, 22, 27, 12, 21];
+/// the modifier lines in `MODIFIER_LINES` order, and whether each reads high
+/// while held.
+pub const MODIFIER_PINS: [(u8, bool); NUM_MODS] = [
+    (5, true),   // SW1 :: Closed Apple
+    (7, true),   // SW0 :: Open Apple
+    (11, false), // Control
+    (9, false),  // RESET
+    (26, false), // Shift
+];
+
+/// the gpio the state machine drives high for each column, in order.
+static COLUMN_MASKS: [u32; NUM_COLS] = column_masks();
+
+type Frame = &'static mut [u32; NUM_COLS];
+type Masks = &'static [u32; NUM_COLS];
+
+pub struct MatrixScanner {
+    rx: Option<Transfer<Channel<CH0>, Channel<CH1>, Rx<PIO0SM0>, Frame, WriteNext<Frame>>>,
+    tx: Option<Transfer<Channel<CH2>, Channel<CH3>, Masks, Tx<PIO0SM0>, ReadNext<Masks>>>,
 }
 
-impl DecoderMatix {
-    pub fn init(pins: Pins) -> Self {
-        //let in_pin = pins.gpio26.into_mode::<Input<>>();
+impl MatrixScanner {
+    /// start scanning, the columns have to be in `FunctionPio0` already.
+    pub fn new(
+        pio: &mut PIO<PIO0>,
+        sm: UninitStateMachine<PIO0SM0>,
+        channels: (Channel<CH0>, Channel<CH1>, Channel<CH2>, Channel<CH3>),
+        frames: (Frame, Frame),
+        sys_freq: u32,
+    ) -> Self {
+        // at one instruction per µs a column is held for 20µs before the rows
+        // are sampled and released for 20µs after, ~380µs a frame.
+        let program = pio_proc::pio_asm!(
+            ".wrap_target",
+            "    pull block",
+            "    out pins, 32",
+            "    set y, 19",
+            "settle:",
+            "    jmp y-- settle",
+            "    in pins, 32",
+            "    push block",
+            "    mov pins, null",
+            "    set y, 19",
+            "release:",
+            "    jmp y-- release",
+            ".wrap",
+        );
+        let installed = pio.install(&program.program).unwrap();
+        let (mut sm, rx, tx) = PIOBuilder::from_program(installed)
+            // every gpio, though only the columns are the pio's to drive.
+            .out_pins(0, 32)
+            .in_pin_base(0)
+            .clock_divisor_fixed_point((sys_freq / 1_000_000) as u16, 0)
+            .build(sm);
+        sm.set_pindirs(COLUMN_PINS.iter().map(|pin| (*pin, PinDir::Output)));
+        sm.start();
 
+        let (ch0, ch1, ch2, ch3) = channels;
+        let rx = Config::new((ch0, ch1), rx, frames.0)
+            .start()
+            .write_next(frames.1);
+        let tx = Config::new((ch2, ch3), &COLUMN_MASKS, tx)
+            .start()
+            .read_next(&COLUMN_MASKS);
         Self {
-            pins: [[].to_vec()].to_vec(),
+            rx: Some(rx),
+            tx: Some(tx),
         }
     }
-    pub fn read_pins() -> Self {
-        Self { pins: [].to_vec() }
+
+    /// the raw modifier lines and matrix of the oldest frame not yet read,
+    /// waiting on it if the state machine isn't done. the pio stops two frames
+    /// ahead of the reader, so the frame is at most one scan period old.
+    pub fn scan(&mut self) -> ([bool; NUM_MODS], [[bool; NUM_ROWS]; NUM_COLS]) {
+        let (frame, rx) = self.rx.take().unwrap().wait();
+        let sample = decode(frame);
+        self.rx = Some(rx.write_next(frame));
+
+        // the masks went out ahead of the samples, so this doesn't block.
+        let (masks, tx) = self.tx.take().unwrap().wait();
+        self.tx = Some(tx.read_next(masks));
+        sample
+    }
+}
+
+/// split a frame of gpio samples, one per column, into the rows of each
+/// column and the modifier lines.
+fn decode(frame: &[u32; NUM_COLS]) -> ([bool; NUM_MODS], [[bool; NUM_ROWS]; NUM_COLS]) {
+    let mut raw_matrix = [[false; NUM_ROWS]; NUM_COLS];
+    for (sample, matrix_col) in frame.iter().zip(raw_matrix.iter_mut()) {
+        for (pin, matrix_row) in ROW_PINS.iter().zip(matrix_col.iter_mut()) {
+            *matrix_row = sample & (1 << pin) != 0;
+        }
+    }
+
+    // the modifier lines aren't strobed, the last column's sample is as
+    // good as any.
+    let sample = frame[NUM_COLS - 1];
+    let raw_modifiers =
+        MODIFIER_PINS.map(|(pin, active_high)| (sample & (1 << pin) != 0) == active_high);
+    (raw_modifiers, raw_matrix)
+}
+
+const fn column_masks() -> [u32; NUM_COLS] {
+    let mut masks = [0; NUM_COLS];
+    let mut col = 0;
+    while col < NUM_COLS {
+        masks[col] = 1 << COLUMN_PINS[col];
+        col += 1;
     }
+    masks
 }
@@ -0,0 +1,122 @@
+/// the keyboard interface, a composite of three reports told apart by their
+/// report id, see `modern_iie_core::kb::oracle::KbOracleReports::to_bytes`:
+/// the keyboard (`REPORT_ID_KEYBOARD`), consumer control (`REPORT_ID_CONSUMER`)
+/// and system control (`REPORT_ID_SYSTEM`).
+pub const A2PI_DESCRIPTOR: &[u8] = &[
+    0x05, 0x01, // Usage Page (Generic Desktop Ctrls)
+    0x09, 0x06, // Usage (Keyboard)
+    0xA1, 0x01, // Collection (Application)
+    0x85, 0x01, //   Report ID (1)
+    // Modifier Keys
+    0x05, 0x07, //   Usage Page (Kbrd/Keypad)
+    0x19, 0xE0, //   Usage Minimum (0xE0)
+    0x29, 0xE7, //   Usage Maximum (0xE7)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x25, 0x01, //   Logical Maximum (1)
+    0x95, 0x08, //   Report Count (8)
+    0x75, 0x01, //   Report Size (1)
+    0x81, 0x02, //   Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    // Reserved Byte
+    0x95, 0x01, //   Report Count (1)
+    0x75, 0x08, //   Report Size (8)
+    0x81, 0x01, //   Input (Const,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    // LEDs
+    0x05, 0x08, //   Usage Page (LEDs)
+    0x19, 0x01, //   Usage Minimum (Num Lock)
+    0x29, 0x05, //   Usage Maximum (Kana)
+    0x95, 0x05, //   Report Count (5)
+    0x75, 0x01, //   Report Size (1)
+    0x91,
+    0x02, //   Output (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position,Non-volatile)
+    // LED Padding
+    0x95, 0x01, //   Report Count (1)
+    0x75, 0x03, //   Report Size (3)
+    0x91,
+    0x01, //   Output (Const,Var,Abs,No Wrap,Linear,Preferred State,No Null Position,Non-volatile)
+    // Keycodes
+    0x05, 0x07, //   Usage Page (Kbrd/Keypad)
+    0x19, 0x00, //   Usage Minimum (0x00)
+    0x29, 0xDD, //   Usage Maximum (0xDD) - TODO - double check this
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xFF,
+    0x00, //   Logical Maximum (255) - TOOD - double check max and trailing 0x00 byte
+    0x95, 0x06, //   Report Count (6)
+    0x75, 0x08, //   Report Size (8)
+    0x81, 0x00, //   Input (Data,Array,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    0xC0, // End Collection
+    // Consumer Control
+    0x05, 0x0C, // Usage Page (Consumer)
+    0x09, 0x01, // Usage (Consumer Control)
+    0xA1, 0x01, // Collection (Application)
+    0x85, 0x02, //   Report ID (2)
+    0x19, 0x00, //   Usage Minimum (Unassigned)
+    0x2A, 0xFF, 0x03, //   Usage Maximum (0x03FF)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xFF, 0x03, //   Logical Maximum (1023)
+    0x95, 0x01, //   Report Count (1)
+    0x75, 0x10, //   Report Size (16)
+    0x81, 0x00, //   Input (Data,Array,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    0xC0, // End Collection
+    // System Control
+    0x05, 0x01, // Usage Page (Generic Desktop Ctrls)
+    0x09, 0x80, // Usage (Sys Control)
+    0xA1, 0x01, // Collection (Application)
+    0x85, 0x03, //   Report ID (3)
+    0x19, 0x81, //   Usage Minimum (Sys Power Down)
+    0x29, 0x83, //   Usage Maximum (Sys Wake Up)
+    0x16, 0x81, 0x00, //   Logical Minimum (129)
+    0x26, 0x83, 0x00, //   Logical Maximum (131)
+    0x95, 0x01, //   Report Count (1)
+    0x75, 0x08, //   Report Size (8)
+    0x81, 0x00, //   Input (Data,Array,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    0xC0, // End Collection
+];
+
+/// the nkro keyboard interface, the modifier byte then a bitmap of the
+/// keyboard usages below them, see `modern_iie_core::kb::report::NkroReport`.
+/// report protocol hosts get every key from here, boot protocol hosts keep to
+/// the 6kro report of `A2PI_DESCRIPTOR`.
+pub const NKRO_DESCRIPTOR: &[u8] = &[
+    0x05, 0x01, // Usage Page (Generic Desktop Ctrls)
+    0x09, 0x06, // Usage (Keyboard)
+    0xA1, 0x01, // Collection (Application)
+    // Modifier Keys
+    0x05, 0x07, //   Usage Page (Kbrd/Keypad)
+    0x19, 0xE0, //   Usage Minimum (0xE0)
+    0x29, 0xE7, //   Usage Maximum (0xE7)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x25, 0x01, //   Logical Maximum (1)
+    0x95, 0x08, //   Report Count (8)
+    0x75, 0x01, //   Report Size (1)
+    0x81, 0x02, //   Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    // Key Bitmap
+    0x19, 0x00, //   Usage Minimum (0x00)
+    0x29, 0xDF, //   Usage Maximum (0xDF)
+    0x96, 0xE0, 0x00, //   Report Count (224)
+    0x75, 0x01, //   Report Size (1)
+    0x81, 0x02, //   Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    0xC0, // End Collection
+];
+
+/// the vendor defined configuration interface, `CONFIG_REPORT_LEN` byte
+/// reports each way. see `modern_iie_core::config::protocol`.
+pub const CONFIG_DESCRIPTOR: &[u8] = &[
+    0x06, 0x60, 0xFF, // Usage Page (Vendor Defined 0xFF60)
+    0x09, 0x61, // Usage (0x61)
+    0xA1, 0x01, // Collection (Application)
+    // Responses
+    0x09, 0x62, //   Usage (0x62)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xFF, 0x00, //   Logical Maximum (255)
+    0x95, 0x20, //   Report Count (32)
+    0x75, 0x08, //   Report Size (8)
+    0x81, 0x02, //   Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+    // Requests
+    0x09, 0x63, //   Usage (0x63)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xFF, 0x00, //   Logical Maximum (255)
+    0x95, 0x20, //   Report Count (32)
+    0x75, 0x08, //   Report Size (8)
+    0x91, 0x02, //   Output (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position,Non-volatile)
+    0xC0, // End Collection
+];
@@ -1,7 +1,3 @@
 pub mod decoder;
-pub mod driver;
-pub mod handshake;
-pub mod input;
-pub mod kbmap;
-pub mod oracle;
-pub mod state;
+pub mod descriptor;
+pub mod requests;
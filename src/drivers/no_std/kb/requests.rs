@@ -0,0 +1,209 @@
+//! the hid class requests `usbd_hid`'s `HIDClass` leaves unanswered. GET_REPORT
+//! is answered with the report last sent, SET_IDLE/GET_IDLE keep an idle rate
+//! per interface and report id, at which `idle_report` repeats an unchanged
+//! report, and SET_REPORT takes the keyboard leds. uefi setup screens and kvm
+//! switches lean on the first two, hosts without an interrupt out pipe set the
+//! leds with the last.
+//!
+//! `usb_device` offers a control request to each class in turn until one
+//! answers it, so polling `HidRequests` ahead of the hid classes lets it take
+//! these while SET_PROTOCOL/GET_PROTOCOL fall through to `HIDClass`.
+
+use modern_iie_core::kb::leds::Leds;
+use modern_iie_core::kb::oracle::{
+    KbOracleReports, MediaKeyboardReport, SystemControlReport, REPORT_ID_CONSUMER, REPORT_ID_KEYBOARD, REPORT_ID_SYSTEM,
+};
+use modern_iie_core::kb::report::{boot_bytes, NkroReport, NKRO_REPORT_LEN};
+use usb_device::class_prelude::*;
+use usb_device::control::{Recipient, RequestType};
+
+// the interfaces in the order `main` allocates their `HIDClass`es.
+pub const BOOT_INTERFACE: u16 = 0;
+pub const NKRO_INTERFACE: u16 = 1;
+
+const HID_REQ_GET_REPORT: u8 = 0x01;
+const HID_REQ_GET_IDLE: u8 = 0x02;
+const HID_REQ_SET_REPORT: u8 = 0x09;
+const HID_REQ_SET_IDLE: u8 = 0x0A;
+
+const REPORT_TYPE_INPUT: u8 = 0x01;
+const REPORT_TYPE_OUTPUT: u8 = 0x02;
+
+/// the idle rate is in units of 4ms.
+const IDLE_UNIT_US: u32 = 4_000;
+/// the hid spec's recommended idle rate for a boot keyboard, 500ms.
+const BOOT_IDLE_DEFAULT: u8 = 125;
+
+pub struct HidRequests {
+    /// the idle rate of the boot interface by report id, 0 being every report
+    /// and the report of the boot protocol. 0 repeats nothing.
+    boot_idle: [u8; 4],
+    nkro_idle: u8,
+    /// the reports as last sent, see `record`.
+    keyboard: NkroReport,
+    consumer: u16,
+    system: u8,
+    keyboard_sent_at: u32,
+    /// the leds of the host's last output report.
+    leds: Leds,
+}
+
+impl HidRequests {
+    pub fn new() -> Self {
+        Self {
+            boot_idle: [BOOT_IDLE_DEFAULT; 4],
+            nkro_idle: 0,
+            keyboard: NkroReport::empty(),
+            consumer: 0,
+            system: 0,
+            keyboard_sent_at: 0,
+            leds: Leds::NONE,
+        }
+    }
+
+    pub fn leds(&self) -> Leds {
+        self.leds
+    }
+
+    /// note the leds of an output report of the boot interface, it only
+    /// leads with the report id under the report protocol.
+    pub fn output_report(&mut self, report: &[u8], boot: bool) {
+        match (boot, report) {
+            (true, [leds, ..]) | (false, [REPORT_ID_KEYBOARD, leds, ..]) => {
+                self.leds = Leds::from_report(*leds)
+            }
+            _ => {}
+        }
+    }
+
+    /// note a report the host was sent at `now` (µs).
+    pub fn record(&mut self, report: &KbOracleReports, now: u32) {
+        match report {
+            KbOracleReports::Keyboard(k) => {
+                self.keyboard = *k;
+                self.keyboard_sent_at = now;
+            }
+            KbOracleReports::Consumer(c) => self.consumer = c.usage_id,
+            KbOracleReports::System(s) => self.system = s.usage_id,
+        }
+    }
+
+    /// the keyboard report again once the host's idle rate has passed without
+    /// a change, `None` while the host only wants changes.
+    pub fn idle_report(&self, boot: bool, now: u32) -> Option<KbOracleReports> {
+        let idle = match boot {
+            true => self.boot_idle[0],
+            false => self.nkro_idle,
+        };
+        if idle == 0 || now.wrapping_sub(self.keyboard_sent_at) < idle as u32 * IDLE_UNIT_US {
+            return None;
+        }
+        Some(KbOracleReports::Keyboard(self.keyboard))
+    }
+
+    /// the current input report `report_id` of `interface`, as GET_REPORT
+    /// answers it.
+    fn report(&self, interface: u16, report_id: u8) -> Option<([u8; NKRO_REPORT_LEN], usize)> {
+        let mut buf = [0u8; NKRO_REPORT_LEN];
+        let mut put = |bytes: &[u8]| {
+            buf[..bytes.len()].copy_from_slice(bytes);
+            bytes.len()
+        };
+
+        let len = match (interface, report_id) {
+            // the boot protocol's report, it has no id.
+            (BOOT_INTERFACE, 0) => put(&boot_bytes(&self.keyboard.to_boot())),
+            (BOOT_INTERFACE, _) => {
+                let report = match report_id {
+                    REPORT_ID_KEYBOARD => KbOracleReports::Keyboard(self.keyboard),
+                    REPORT_ID_CONSUMER => KbOracleReports::Consumer(MediaKeyboardReport {
+                        usage_id: self.consumer,
+                    }),
+                    REPORT_ID_SYSTEM => KbOracleReports::System(SystemControlReport {
+                        usage_id: self.system,
+                    }),
+                    _ => return None,
+                };
+                let (bytes, len) = report.to_bytes();
+                put(&bytes[..len])
+            }
+            (NKRO_INTERFACE, 0) => put(&self.keyboard.to_bytes()),
+            _ => return None,
+        };
+        Some((buf, len))
+    }
+
+    fn idle_mut(&mut self, interface: u16, report_id: u8) -> Option<&mut u8> {
+        match interface {
+            BOOT_INTERFACE => self.boot_idle.get_mut(report_id as usize),
+            NKRO_INTERFACE if report_id == 0 => Some(&mut self.nkro_idle),
+            _ => None,
+        }
+    }
+}
+
+impl<B: UsbBus> UsbClass<B> for HidRequests {
+    fn control_in(&mut self, xfer: ControlIn<B>) {
+        let req = *xfer.request();
+        if req.request_type != RequestType::Class || req.recipient != Recipient::Interface {
+            return;
+        }
+        let [report_id, report_type] = req.value.to_le_bytes();
+
+        match req.request {
+            HID_REQ_GET_REPORT if report_type == REPORT_TYPE_INPUT => {
+                match self.report(req.index, report_id) {
+                    Some((buf, len)) => {
+                        let len = len.min(req.length as usize);
+                        xfer.accept_with(&buf[..len]).ok();
+                    }
+                    None if matches!(req.index, BOOT_INTERFACE | NKRO_INTERFACE) => {
+                        xfer.reject().ok();
+                    }
+                    None => {}
+                }
+            }
+            HID_REQ_GET_IDLE => {
+                if let Some(idle) = self.idle_mut(req.index, report_id) {
+                    let idle = *idle;
+                    xfer.accept_with(&[idle]).ok();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn control_out(&mut self, xfer: ControlOut<B>) {
+        let req = *xfer.request();
+        if req.request_type != RequestType::Class || req.recipient != Recipient::Interface {
+            return;
+        }
+        let [report_id, value] = req.value.to_le_bytes();
+
+        match req.request {
+            HID_REQ_SET_REPORT if value == REPORT_TYPE_OUTPUT => {
+                if req.index != BOOT_INTERFACE {
+                    return;
+                }
+                // the data leads with the report id unless it's 0.
+                self.output_report(xfer.data(), report_id == 0);
+            }
+            HID_REQ_SET_IDLE => match (req.index, report_id) {
+                // every report of the interface.
+                (BOOT_INTERFACE, 0) => self.boot_idle = [value; 4],
+                _ => match self.idle_mut(req.index, report_id) {
+                    Some(idle) => *idle = value,
+                    None => return,
+                },
+            },
+            _ => return,
+        }
+        xfer.accept().ok();
+    }
+}
+
+impl Default for HidRequests {
+    fn default() -> Self {
+        Self::new()
+    }
+}
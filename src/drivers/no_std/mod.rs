@@ -1,2 +1,10 @@
 #[cfg(feature = "no-std")]
+pub mod crash;
+#[cfg(feature = "no-std")]
 pub mod kb;
+#[cfg(feature = "no-std")]
+pub mod scheduler;
+#[cfg(feature = "no-std")]
+pub mod storage;
+#[cfg(feature = "no-std")]
+pub mod usb;
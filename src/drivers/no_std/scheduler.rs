@@ -0,0 +1,121 @@
+//! the scan period, kept by a timer alarm rather than a delay after each scan
+//! so it doesn't stretch with however long the oracle took. the alarm is
+//! re-armed from its own deadline, never from when the interrupt ran, and a
+//! period the scan loop couldn't keep up with is counted as an overrun.
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use hal::pac::{self, interrupt};
+use hal::timer::{Alarm, Alarm0, Instant};
+use rp2040_hal as hal;
+
+static SCAN_TIMER: Mutex<RefCell<Option<ScanTimer>>> = Mutex::new(RefCell::new(None));
+
+struct ScanTimer {
+    alarm: Alarm0,
+    period_us: u64,
+    /// the counter value the alarm is armed for.
+    deadline: u64,
+    /// periods elapsed that the scan loop hasn't taken yet.
+    pending: u32,
+    /// periods that went by without a scan of their own.
+    overruns: u32,
+}
+
+/// tick every `period_ms` from now on, see `wait`.
+pub fn start(mut alarm: Alarm0, period_ms: u32) {
+    let period_us = period_ms as u64 * 1_000;
+    let deadline = counter() + period_us;
+    alarm.schedule_at(Instant::from_ticks(deadline)).ok();
+    alarm.enable_interrupt();
+    critical_section::with(|cs| {
+        SCAN_TIMER.borrow_ref_mut(cs).replace(ScanTimer {
+            alarm,
+            period_us,
+            deadline,
+            pending: 0,
+            overruns: 0,
+        })
+    });
+    unsafe {
+        pac::NVIC::unmask(pac::Interrupt::TIMER_IRQ_0);
+    }
+}
+
+/// sleep until the next tick, returning right away if one is already due.
+/// more than one due means the scans fell behind, all but one are overruns.
+pub fn wait() {
+    loop {
+        // masked from the check to the `wfi` so a tick can't slip in between,
+        // a pending interrupt still wakes the core.
+        cortex_m::interrupt::disable();
+        let ticked = critical_section::with(|cs| {
+            let mut scan_timer = SCAN_TIMER.borrow_ref_mut(cs);
+            let scan_timer = scan_timer.as_mut().unwrap();
+            if scan_timer.pending == 0 {
+                return false;
+            }
+            scan_timer.overruns += scan_timer.pending - 1;
+            scan_timer.pending = 0;
+            true
+        });
+        if !ticked {
+            cortex_m::asm::wfi();
+        }
+        unsafe { cortex_m::interrupt::enable() };
+        if ticked {
+            return;
+        }
+    }
+}
+
+/// the overruns since boot.
+pub fn overruns() -> u32 {
+    critical_section::with(|cs| {
+        SCAN_TIMER
+            .borrow_ref(cs)
+            .as_ref()
+            .map_or(0, |scan_timer| scan_timer.overruns)
+    })
+}
+
+/// µs since boot off the full 64 bit counter, which won't wrap.
+pub fn counter() -> u64 {
+    let timer = unsafe { &*pac::TIMER::ptr() };
+    // the high word is re-read in case the low one wrapped in between.
+    loop {
+        let high = timer.timerawh.read().bits();
+        let low = timer.timerawl.read().bits();
+        if timer.timerawh.read().bits() == high {
+            return ((high as u64) << 32) | low as u64;
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[interrupt]
+fn TIMER_IRQ_0() {
+    critical_section::with(|cs| {
+        let mut scan_timer = SCAN_TIMER.borrow_ref_mut(cs);
+        let Some(scan_timer) = scan_timer.as_mut() else {
+            return;
+        };
+        scan_timer.alarm.clear_interrupt();
+
+        // something held the interrupt off for whole periods, they're ticks
+        // all the same.
+        let now = counter();
+        loop {
+            scan_timer.deadline += scan_timer.period_us;
+            scan_timer.pending += 1;
+            if scan_timer.deadline > now {
+                break;
+            }
+        }
+        scan_timer
+            .alarm
+            .schedule_at(Instant::from_ticks(scan_timer.deadline))
+            .ok();
+    });
+}
@@ -0,0 +1,199 @@
+//! regions of the external flash kept after the program image, see `memory.x`.
+//! flash is memory mapped through xip so reading a region is just a slice.
+//!
+//! xip is unavailable while the flash is erased or programmed, so nothing may
+//! run from it meanwhile: core 0 writes with its interrupts masked and parks
+//! core 1 first. core 1 checks for the request once per scan, acks it and
+//! spins in ram with its own interrupts masked until core 0 is done. the
+//! handshake is a pair of flags rather than the sio fifo, core 0's side of the
+//! fifo carries the scan.
+//!
+//! a write may come from `USBCTRL_IRQ` rather than the scan loop feeding the
+//! watchdog, so the watchdog is fed around every sector written and a keymap
+//! spanning several sectors is written one at a time.
+
+use alloc::vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use modern_iie_core::config::KeyMapStore;
+use modern_iie_core::settings::{SettingsFlash, SETTINGS_SECTOR_LEN};
+use rp2040_hal::pac;
+
+extern "C" {
+    static __keymap_start: u8;
+    static __keymap_end: u8;
+    static __settings_start: u8;
+    static __settings_end: u8;
+}
+
+const XIP_BASE: usize = 0x1000_0000;
+// the smallest erasable unit of the external flash.
+const SECTOR_SIZE: usize = 4096;
+// the smallest programmable unit.
+const PAGE_SIZE: usize = 256;
+
+/// set once core 1 has been spawned and has to be parked for a write.
+static CORE1_RUNNING: AtomicBool = AtomicBool::new(false);
+/// core 0 asking core 1 to park.
+static PAUSE: AtomicBool = AtomicBool::new(false);
+/// core 1 parked in ram.
+static PARKED: AtomicBool = AtomicBool::new(false);
+/// the reload value of the running watchdog, 0 until it's started.
+static WATCHDOG_LOAD: AtomicU32 = AtomicU32::new(0);
+
+/// called by core 0 in the critical section core 1 is spawned in, so a write
+/// can't start in between.
+pub fn core1_started() {
+    CORE1_RUNNING.store(true, Ordering::Release);
+}
+
+/// called by core 0 once the watchdog runs with a period of `period_ms`.
+pub fn watchdog_started(period_ms: u32) {
+    // it counts down twice per tick (erratum rp2040-e1), as `hal::Watchdog`
+    // makes up for.
+    WATCHDOG_LOAD.store(period_ms * 1_000 * 2, Ordering::Release);
+}
+
+/// feed the watchdog as `hal::Watchdog::feed` does, which core 0's main loop
+/// owns.
+fn feed_watchdog() {
+    let load = WATCHDOG_LOAD.load(Ordering::Acquire);
+    if load != 0 {
+        unsafe { (*pac::WATCHDOG::ptr()).load.write(|w| w.bits(load)) };
+    }
+}
+
+/// called by core 1 once per scan, parks it if core 0 is about to write.
+pub fn park_core1_if_asked() {
+    if PAUSE.load(Ordering::Acquire) {
+        cortex_m::interrupt::free(|_| unsafe { park(PAUSE.as_ptr(), PARKED.as_ptr()) });
+    }
+}
+
+/// ack the pause and spin until it's lifted. this runs from ram and calls
+/// nothing, not even the atomics (a debug build doesn't inline them), so the
+/// flags are read and written by hand.
+#[inline(never)]
+#[link_section = ".data.ram_func"]
+unsafe fn park(pause: *mut bool, parked: *mut bool) {
+    core::arch::asm!("strb {one}, [{parked}]", one = in(reg) 1u32, parked = in(reg) parked);
+    loop {
+        let paused: u32;
+        core::arch::asm!(
+            "ldrb {paused}, [{pause}]",
+            paused = out(reg) paused,
+            pause = in(reg) pause,
+        );
+        if paused == 0 {
+            break;
+        }
+    }
+    core::arch::asm!("strb {zero}, [{parked}]", zero = in(reg) 0u32, parked = in(reg) parked);
+}
+
+/// run a flash write with core 1 parked and interrupts masked, feeding the
+/// watchdog on either side of it. keep it to a sector, an erase alone can take
+/// a few hundred ms.
+fn with_flash<R>(write: impl FnOnce() -> R) -> R {
+    feed_watchdog();
+    let core1 = CORE1_RUNNING.load(Ordering::Acquire);
+    if core1 {
+        PAUSE.store(true, Ordering::Release);
+        while !PARKED.load(Ordering::Acquire) {}
+    }
+    let result = cortex_m::interrupt::free(|_| write());
+    if core1 {
+        PAUSE.store(false, Ordering::Release);
+        // back out of ram before a later write can pause it again.
+        while PARKED.load(Ordering::Acquire) {}
+    }
+    feed_watchdog();
+    result
+}
+
+/// the serialized keymap region, erased (0xFF) until a keymap is stored.
+pub fn keymap_region() -> &'static [u8] {
+    unsafe {
+        let start = core::ptr::addr_of!(__keymap_start);
+        let end = core::ptr::addr_of!(__keymap_end);
+        core::slice::from_raw_parts(start, end as usize - start as usize)
+    }
+}
+
+/// the two sectors of the settings log.
+pub fn settings_region() -> &'static [u8] {
+    unsafe {
+        let start = core::ptr::addr_of!(__settings_start);
+        let end = core::ptr::addr_of!(__settings_end);
+        core::slice::from_raw_parts(start, end as usize - start as usize)
+    }
+}
+
+/// persists keymaps committed over the configuration channel into
+/// `keymap_region`.
+pub struct FlashKeyMapStore;
+
+impl KeyMapStore for FlashKeyMapStore {
+    fn store(&mut self, blob: &[u8]) -> bool {
+        let region = keymap_region();
+
+        // erase whole sectors, padding the tail as if it were already erased.
+        let len = (blob.len() + SECTOR_SIZE - 1) / SECTOR_SIZE * SECTOR_SIZE;
+        if len > region.len() {
+            defmt::error!("keymap of {} bytes exceeds its region", blob.len());
+            return false;
+        }
+        let mut sectors = vec![0xFFu8; len];
+        sectors[..blob.len()].copy_from_slice(blob);
+
+        let offset = (region.as_ptr() as usize - XIP_BASE) as u32;
+        for (index, sector) in sectors.chunks(SECTOR_SIZE).enumerate() {
+            let offset = offset + (index * SECTOR_SIZE) as u32;
+            with_flash(|| unsafe {
+                rp2040_flash::flash::flash_range_erase_and_program(offset, sector, true);
+            });
+        }
+
+        region[..blob.len()] == *blob
+    }
+}
+
+/// the settings log's sectors in `settings_region`.
+pub struct FlashSettings;
+
+impl FlashSettings {
+    fn offset(index: usize) -> u32 {
+        (settings_region().as_ptr() as usize - XIP_BASE + index * SETTINGS_SECTOR_LEN) as u32
+    }
+}
+
+impl SettingsFlash for FlashSettings {
+    fn sector(&self, index: usize) -> &[u8] {
+        &settings_region()[index * SETTINGS_SECTOR_LEN..(index + 1) * SETTINGS_SECTOR_LEN]
+    }
+
+    fn erase(&mut self, index: usize) {
+        with_flash(|| unsafe {
+            rp2040_flash::flash::flash_range_erase(
+                Self::offset(index),
+                SETTINGS_SECTOR_LEN as u32,
+                true,
+            );
+        });
+    }
+
+    fn program(&mut self, index: usize, offset: usize, bytes: &[u8]) {
+        // whole pages only, the rest of them left as 0xFF programs nothing.
+        let start = offset / PAGE_SIZE * PAGE_SIZE;
+        let end = (offset + bytes.len() + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE;
+        let mut pages = vec![0xFFu8; end - start];
+        pages[offset - start..offset - start + bytes.len()].copy_from_slice(bytes);
+
+        with_flash(|| unsafe {
+            rp2040_flash::flash::flash_range_program(
+                Self::offset(index) + start as u32,
+                &pages,
+                true,
+            );
+        });
+    }
+}
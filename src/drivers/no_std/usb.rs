@@ -0,0 +1,25 @@
+//! the usb identity the firmware enumerates with, fixed at build time by
+//! `build.rs`, and a serial number unique to the board.
+
+use usbd_hid::hid_class::HidCountryCode;
+
+include!(concat!(env!("OUT_DIR"), "/usb_identity.rs"));
+
+/// the flash's 64 bit unique id in hex, so that several boards on one host
+/// can be told apart. read once, before usb is up.
+pub fn serial_number() -> &'static str {
+    let mut id = [0u8; 8];
+    // xip is unavailable while the id is read, see `storage::FlashKeyMapStore`.
+    cortex_m::interrupt::free(|_| unsafe {
+        rp2040_flash::flash::flash_unique_id(&mut id, true);
+    });
+
+    let serial = cortex_m::singleton!(: [u8; 16] = [0; 16]).unwrap();
+    for (byte, hex) in id.iter().zip(serial.chunks_exact_mut(2)) {
+        hex[0] = HEX_DIGITS[(byte >> 4) as usize];
+        hex[1] = HEX_DIGITS[(byte & 0x0F) as usize];
+    }
+    core::str::from_utf8(serial).unwrap()
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
@@ -1,14 +0,0 @@
-#[cfg(feature = "std")]
-pub mod driver;
-#[cfg(feature = "std")]
-pub mod events;
-#[cfg(feature = "std")]
-pub mod handshake;
-#[cfg(feature = "std")]
-pub mod input;
-#[cfg(feature = "std")]
-pub mod kbmap;
-#[cfg(feature = "std")]
-pub mod state;
-#[cfg(feature = "std")]
-pub mod vdev;
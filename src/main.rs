@@ -4,18 +4,30 @@
 extern crate alloc;
 
 mod drivers;
-mod utils;
 
-use crate::drivers::no_std::kb::decoder::{Debounce, KeyScan, NUM_COLS, NUM_MODS, NUM_ROWS};
-use crate::drivers::no_std::kb::input::A2PI_DESCRIPTOR;
+use crate::drivers::no_std::crash;
+use crate::drivers::no_std::kb::decoder::MatrixScanner;
+use crate::drivers::no_std::kb::descriptor::{A2PI_DESCRIPTOR, CONFIG_DESCRIPTOR, NKRO_DESCRIPTOR};
+use crate::drivers::no_std::kb::requests::HidRequests;
+use crate::drivers::no_std::scheduler;
+use crate::drivers::no_std::storage::{self, FlashKeyMapStore, FlashSettings};
+use crate::drivers::no_std::usb;
+use alloc::collections::VecDeque;
 use alloc::vec::Vec;
 use core::borrow::{Borrow, BorrowMut};
-use core::{cell::RefCell, convert::Infallible};
+use core::cell::RefCell;
 use critical_section::Mutex;
-use drivers::no_std::kb::oracle::KbOracleReports;
+use modern_iie_core::kb::decoder::{
+    ChordHold, Debounce, DebounceConfig, DebounceMode, KeyScan, ScanEvent, BOOTLOADER_CHORD,
+    BOOTLOADER_HOLD_MS, NUM_COLS, NUM_MODS, NUM_ROWS,
+};
+use modern_iie_core::kb::leds::Leds;
+use modern_iie_core::kb::oracle::KbOracleReports;
+use modern_iie_core::kb::report::boot_bytes;
+use modern_iie_core::settings::{Setting, SettingsLog, SettingsStore};
 use embedded_hal::digital::v2::{InputPin, OutputPin};
 use hal::gpio::bank0::{Gpio16, Gpio17, Gpio18};
-use hal::gpio::{Input, Output, Pin, PullDown, PullUp, PushPull};
+use hal::gpio::{FunctionPio0, Input, Output, Pin, PullDown, PullUp, PushPull};
 use hal::multicore::{Multicore, Stack};
 use usbd_human_interface_device::device::consumer::{ConsumerControl, ConsumerControlConfig};
 use usbd_human_interface_device::usb_class::{UsbHidClass, UsbHidClassBuilder};
@@ -23,8 +35,13 @@ use usbd_human_interface_device::usb_class::{UsbHidClass, UsbHidClassBuilder};
 use usb_device::class_prelude::*;
 use usb_device::prelude::*;
 
-use crate::drivers::no_std::kb::driver::KbDriver;
-use crate::drivers::shared::kb::KeyboardDriver;
+use modern_iie_core::config::protocol::CONFIG_REPORT_LEN;
+use modern_iie_core::config::ConfigChannel;
+use modern_iie_core::health::{Health, ResetReason};
+use modern_iie_core::kb::driver::KbDriver;
+use modern_iie_core::kb::kbmap::KeyMap;
+use modern_iie_core::shared::kb::KeyboardDriver;
+use modern_iie_core::shared::queue::ReportQueue;
 use cortex_m::prelude::_embedded_hal_timer_CountDown;
 use cortex_m::singleton;
 use embedded_alloc::Heap;
@@ -32,13 +49,14 @@ use frunk::{HCons, HNil, ToMut};
 use fugit::{ExtU32, HertzU32, RateExtU32};
 use hal::clocks::Clock;
 use hal::dma::DMAExt;
+use hal::pio::PIOExt;
 use hal::uart::{DataBits, StopBits, UartConfig};
 use hal::usb::UsbBus;
-use panic_probe as _;
 use usb_device::class::UsbClass;
 use usbd_hid::descriptor::{KeyboardReport, SerializedDescriptor};
 use usbd_hid::hid_class::{
-    HIDClass, HidClassSettings, HidCountryCode, HidProtocol, HidSubClass, ProtocolModeConfig,
+    HIDClass, HidClassSettings, HidCountryCode, HidProtocol, HidProtocolMode, HidSubClass,
+    ProtocolModeConfig,
 };
 
 use rp2040_hal as hal;
@@ -50,8 +68,7 @@ use defmt_rtt as _;
 #[cfg(feature = "serial")]
 use defmt_serial as _;
 
-const SCAN_LOOP_RATE_MS: u32 = 5;
-const DEBOUNCE_TICKS: u8 = 1;
+const REPORT_QUEUE_LEN: usize = 32;
 
 #[link_section = ".boot2"]
 #[used]
@@ -65,13 +82,18 @@ const XTAL_FREQ_HZ: u32 = 12_000_000u32;
 static mut USB_BUS: Option<usb_device::bus::UsbBusAllocator<UsbBus>> = None;
 static mut USB_DEVICE: Option<UsbDevice<'static, UsbBus>> = None;
 static mut USB_HID: Option<HIDClass<'static, UsbBus>> = None;
-static KEYBOARD_REPORT: Mutex<RefCell<KbOracleReports>> =
-    Mutex::new(RefCell::new(KbOracleReports::Keyboard(KeyboardReport {
-        modifier: 0,
-        reserved: 0,
-        leds: 0,
-        keycodes: [0u8; 6],
-    })));
+static mut USB_NKRO_HID: Option<HIDClass<'static, UsbBus>> = None;
+static mut USB_HID_REQUESTS: Option<HidRequests> = None;
+static mut USB_CONFIG_HID: Option<HIDClass<'static, UsbBus>> = None;
+static mut CONFIG_CHANNEL: Option<ConfigChannel> = None;
+/// read once at boot, then only written by `USBCTRL_IRQ`.
+static mut SETTINGS: Option<SettingsLog<FlashSettings>> = None;
+/// reports waiting on `USBCTRL_IRQ`, a macro queues one per step so it has to
+/// hold the longest macro plus the key up report after it.
+static REPORT_QUEUE: Mutex<RefCell<ReportQueue<KbOracleReports, REPORT_QUEUE_LEN>>> =
+    Mutex::new(RefCell::new(ReportQueue::new()));
+/// the host's lock leds, set by `USBCTRL_IRQ` from the output reports.
+static HOST_LEDS: Mutex<RefCell<Leds>> = Mutex::new(RefCell::new(Leds::NONE));
 
 type Pins = (
     Pin<Gpio16, Output<PushPull>>,
@@ -82,8 +104,6 @@ static PINS: Mutex<RefCell<Option<Pins>>> = Mutex::new(RefCell::new(None));
 
 static mut KEY_PRESS_EVENT: [u8; 3] = [0x0; 3];
 
-static mut A2PI_DRIVER_KB: Mutex<RefCell<Option<KbDriver>>> = Mutex::new(RefCell::new(None));
-
 #[rp2040_hal::entry]
 fn main() -> ! {
     {
@@ -96,10 +116,42 @@ fn main() -> ! {
     // -- BEGIN PRELUDE --
     //
 
-    let mut a2pi = KbDriver::init();
+    let key_map = KeyMap::load(storage::keymap_region());
+    let mut a2pi = KbDriver::with_key_map(key_map);
+    unsafe {
+        let mut config_channel = ConfigChannel::new(firmware_version(), &key_map);
+        if let Some(crash_log) = crash::take() {
+            config_channel.set_crash_log(crash_log);
+        }
+        CONFIG_CHANNEL = Some(config_channel);
+    }
+    let (keys_debounce, mods_debounce, scan_rate_ms, watchdog_ms) = unsafe {
+        let settings = SETTINGS.insert(SettingsLog::load(FlashSettings));
+        (
+            debounce_config(
+                settings,
+                Setting::DebounceMode,
+                Setting::DebouncePressTicks,
+                Setting::DebounceReleaseTicks,
+            ),
+            debounce_config(
+                settings,
+                Setting::ModifierDebounceMode,
+                Setting::ModifierPressTicks,
+                Setting::ModifierReleaseTicks,
+            ),
+            settings.get(Setting::ScanRateMs) as u32,
+            settings.get(Setting::WatchdogMs) as u32,
+        )
+    };
 
     let mut pac = pac::Peripherals::take().unwrap();
     let core = pac::CorePeripherals::take().unwrap();
+    let reset_reason = reset_reason(&pac.WATCHDOG, &pac.VREG_AND_CHIP_RESET);
+    defmt::info!("reset by {}", reset_reason);
+    unsafe {
+        CONFIG_CHANNEL.as_mut().unwrap().health = Health::new(reset_reason);
+    }
     let mut watchdog = hal::Watchdog::new(pac.WATCHDOG);
     let clocks = hal::clocks::init_clocks_and_plls(
         XTAL_FREQ_HZ,
@@ -112,7 +164,7 @@ fn main() -> ! {
     )
     .ok()
     .unwrap();
-    let timer = hal::Timer::new(pac.TIMER, &mut pac.RESETS);
+    let mut timer = hal::Timer::new(pac.TIMER, &mut pac.RESETS);
     let mut delay = cortex_m::delay::Delay::new(core.SYST, clocks.system_clock.freq().to_Hz());
     let mut sio = hal::Sio::new(pac.SIO);
     let pins = hal::gpio::Pins::new(
@@ -122,12 +174,6 @@ fn main() -> ! {
         &mut pac.RESETS,
     );
     let sys_freq = clocks.system_clock.freq().to_Hz();
-    let mut mc = Multicore::new(&mut pac.PSM, &mut pac.PPB, &mut sio.fifo);
-    let cores = mc.cores();
-    let core1 = &mut cores[1];
-    let _test = core1.spawn(unsafe { &mut CORE1_STACK.mem }, move || {
-        core1_task(sys_freq)
-    });
 
     let probe_uart = hal::uart::UartPeripheral::new(
         pac.UART0,
@@ -162,16 +208,20 @@ fn main() -> ! {
     let usb_bus = UsbBusAllocator::new(hal_usb_bus);
     unsafe {
         USB_BUS = Some(usb_bus);
+        USB_HID_REQUESTS = Some(HidRequests::new());
     }
+    // the keyboard interfaces are allocated first, in the order
+    // `requests::BOOT_INTERFACE` and `requests::NKRO_INTERFACE` expect.
     let hid_endpoint = HIDClass::new_with_settings(
         unsafe { USB_BUS.as_ref().unwrap() },
         A2PI_DESCRIPTOR,
         1,
         HidClassSettings {
-            subclass: HidSubClass::NoSubClass,
+            subclass: HidSubClass::Boot,
             protocol: HidProtocol::Keyboard,
-            config: ProtocolModeConfig::ForceReport,
-            locale: HidCountryCode::US,
+            // the host picks boot or report protocol, and with it 6kro or nkro.
+            config: ProtocolModeConfig::DefaultBehavior,
+            locale: usb::USB_COUNTRY_CODE,
         },
     );
 
@@ -179,13 +229,45 @@ fn main() -> ! {
         USB_HID = Some(hid_endpoint);
     }
 
+    let nkro_endpoint = HIDClass::new_with_settings(
+        unsafe { USB_BUS.as_ref().unwrap() },
+        NKRO_DESCRIPTOR,
+        1,
+        HidClassSettings {
+            subclass: HidSubClass::NoSubClass,
+            protocol: HidProtocol::Generic,
+            config: ProtocolModeConfig::ForceReport,
+            locale: usb::USB_COUNTRY_CODE,
+        },
+    );
+
+    unsafe {
+        USB_NKRO_HID = Some(nkro_endpoint);
+    }
+
+    let config_endpoint = HIDClass::new_with_settings(
+        unsafe { USB_BUS.as_ref().unwrap() },
+        CONFIG_DESCRIPTOR,
+        10,
+        HidClassSettings {
+            subclass: HidSubClass::NoSubClass,
+            protocol: HidProtocol::Generic,
+            config: ProtocolModeConfig::DefaultBehavior,
+            locale: HidCountryCode::NotSupported,
+        },
+    );
+
+    unsafe {
+        USB_CONFIG_HID = Some(config_endpoint);
+    }
+
     let usb_device = UsbDeviceBuilder::new(
         unsafe { USB_BUS.as_ref().unwrap() },
-        UsbVidPid(0x05ac, 0x0220),
+        UsbVidPid(usb::USB_VID, usb::USB_PID),
     )
-    .manufacturer("Apple Inc.")
-    .product("Apple Keyboard")
-    // .serial_number("00123")
+    .manufacturer(usb::USB_MANUFACTURER)
+    .product(usb::USB_PRODUCT)
+    .serial_number(usb::serial_number())
     .supports_remote_wakeup(true)
     .build();
 
@@ -198,68 +280,126 @@ fn main() -> ! {
     let mut tick_count_down = timer.count_down();
     tick_count_down.start(1.millis());
 
-    let cols: &mut [&mut dyn OutputPin<Error = Infallible>; NUM_COLS] = &mut [
-        &mut pins.gpio13.into_push_pull_output(), // X0
-        &mut pins.gpio17.into_push_pull_output(), // X1
-        &mut pins.gpio15.into_push_pull_output(), // X2
-        &mut pins.gpio19.into_push_pull_output(), // X3
-        &mut pins.gpio20.into_push_pull_output(), // X5
-        &mut pins.gpio18.into_push_pull_output(), // X4
-        &mut pins.gpio28.into_push_pull_output(), // X6
-        &mut pins.gpio16.into_push_pull_output(), // X7
-    ];
-
-    let rows: &[&dyn InputPin<Error = Infallible>; NUM_ROWS] = &[
-        &pins.gpio2.into_pull_down_input(),  // Y0
-        &pins.gpio3.into_pull_down_input(),  // Y1
-        &pins.gpio4.into_pull_down_input(),  // Y2
-        &pins.gpio14.into_pull_down_input(), // Y3
-        &pins.gpio8.into_pull_down_input(),  // Y3
-        &pins.gpio10.into_pull_down_input(), // Y4
-        &pins.gpio22.into_pull_down_input(), // Y5
-        &pins.gpio27.into_pull_down_input(), // Y6
-        &pins.gpio12.into_pull_down_input(), // Y8
-        &pins.gpio21.into_pull_down_input(), // Y9
-    ];
-
-    let modifiers: (
-        &[&dyn InputPin<Error = Infallible>],
-        &[&dyn InputPin<Error = Infallible>],
-    ) = (
-        &[
-            &pins.gpio5.into_pull_down_input(), // SW1 :: Closed Apple
-            &pins.gpio7.into_pull_down_input(), // SW0 :: Open Apple
-        ],
-        &[
-            &pins.gpio11.into_pull_up_input(), // Control
-            &pins.gpio9.into_pull_up_input(),  // RESET
-            &pins.gpio26.into_pull_up_input(), // Shift
-        ],
+    // the pio drives the columns (X0..X7), the rows (Y0..Y9) and modifier
+    // lines only need their pulls, it samples them straight off the pads.
+    let _cols = (
+        pins.gpio13.into_mode::<FunctionPio0>(),
+        pins.gpio17.into_mode::<FunctionPio0>(),
+        pins.gpio15.into_mode::<FunctionPio0>(),
+        pins.gpio19.into_mode::<FunctionPio0>(),
+        pins.gpio20.into_mode::<FunctionPio0>(),
+        pins.gpio18.into_mode::<FunctionPio0>(),
+        pins.gpio28.into_mode::<FunctionPio0>(),
+        pins.gpio16.into_mode::<FunctionPio0>(),
+    );
+    let _rows = (
+        pins.gpio2.into_pull_down_input(),
+        pins.gpio3.into_pull_down_input(),
+        pins.gpio4.into_pull_down_input(),
+        pins.gpio14.into_pull_down_input(),
+        pins.gpio8.into_pull_down_input(),
+        pins.gpio10.into_pull_down_input(),
+        pins.gpio22.into_pull_down_input(),
+        pins.gpio27.into_pull_down_input(),
+        pins.gpio12.into_pull_down_input(),
+        pins.gpio21.into_pull_down_input(),
+    );
+    let _modifiers = (
+        pins.gpio5.into_pull_down_input(), // SW1 :: Closed Apple
+        pins.gpio7.into_pull_down_input(), // SW0 :: Open Apple
+        pins.gpio11.into_pull_up_input(),  // Control
+        pins.gpio9.into_pull_up_input(),   // RESET
+        pins.gpio26.into_pull_up_input(),  // Shift
     );
 
-    let mut debounce: Debounce<NUM_MODS, NUM_ROWS, NUM_COLS> = Debounce::new(DEBOUNCE_TICKS);
+    let (mut pio, sm0, _, _, _) = pac.PIO0.split(&mut pac.RESETS);
+    let dma = pac.DMA.split(&mut pac.RESETS);
+    let matrix = MatrixScanner::new(
+        &mut pio,
+        sm0,
+        (dma.ch0, dma.ch1, dma.ch2, dma.ch3),
+        (
+            singleton!(: [u32; NUM_COLS] = [0; NUM_COLS]).unwrap(),
+            singleton!(: [u32; NUM_COLS] = [0; NUM_COLS]).unwrap(),
+        ),
+        sys_freq,
+    );
 
-    critical_section::with(|cs| {
-        KEYBOARD_REPORT.replace(cs, KbOracleReports::init());
-    });
+    // the CAPS LOCK latch holds the line low while down.
+    let caps_lock = pins.gpio6.into_pull_up_input();
+    // the pico's own led mirrors the host's caps lock.
+    #[cfg(feature = "indicator")]
+    let mut indicator = pins.gpio25.into_push_pull_output();
+
+    let debounce: Debounce<NUM_MODS, NUM_ROWS, NUM_COLS> =
+        Debounce::with_configs(keys_debounce, mods_debounce);
+    let mut bootloader_chord = ChordHold::new(BOOTLOADER_CHORD, BOOTLOADER_HOLD_MS);
 
     unsafe {
         pac::NVIC::unmask(hal::pac::Interrupt::USBCTRL_IRQ);
     };
 
+    // core 1 scans and debounces, so the scan period holds however long the
+    // oracle takes over a macro. it sends the changes over the sio fifo.
+    let alarm = timer.alarm_0().unwrap();
+    let mut mc = Multicore::new(&mut pac.PSM, &mut pac.PPB, &mut sio.fifo);
+    let cores = mc.cores();
+    let core1 = &mut cores[1];
+    cortex_m::interrupt::free(|_| {
+        core1
+            .spawn(unsafe { &mut CORE1_STACK.mem }, move || {
+                core1_task(matrix, debounce, alarm, scan_rate_ms)
+            })
+            .unwrap();
+        storage::core1_started();
+    });
+
+    // fed once per scan, a wedge on either core resets the board.
+    watchdog.pause_on_debug(true);
+    watchdog.start(watchdog_ms.millis());
+    storage::watchdog_started(watchdog_ms);
+
+    // core 0's copy of the debounced scan, kept up to date from the changes.
+    let mut key_scan = KeyScan::released();
+    let mut queued: QueuedReports = [None; 3];
     loop {
-        let processed_reports =
-            a2pi.process_key_event(modifiers, rows, cols, &mut delay, &mut debounce);
+        match ScanEvent::from_word(sio.fifo.read_blocking()) {
+            Some(event @ ScanEvent::ScanEnd { .. }) => key_scan.apply(event),
+            Some(event) => {
+                key_scan.apply(event);
+                continue;
+            }
+            None => continue,
+        }
+        watchdog.feed();
+
+        a2pi.leds = critical_section::with(|cs| *HOST_LEDS.borrow_ref(cs));
+        #[cfg(feature = "indicator")]
+        indicator
+            .set_state(a2pi.leds.contains(Leds::CAPS_LOCK).into())
+            .ok();
+
+        crash::record_scan(key_scan.modifiers().bits(), key_scan.scan_codes());
+        // checked ahead of the keymap so no layer can shadow it.
+        let now_ms = (scheduler::counter() / 1_000) as u32;
+        if bootloader_chord.tick(key_scan.modifiers(), now_ms) {
+            defmt::info!("rebooting into the usb bootloader");
+            hal::rom_data::reset_to_usb_boot(0, 0);
+        }
+        let processed_reports = a2pi.process_key_event(key_scan);
         if let Some(reports) = processed_reports {
-            // defmt::info!("!-----! {}", reports.len());
-            critical_section::with(|cs| {
-                for report in reports {
-                    KEYBOARD_REPORT.replace(cs, report);
-                    // delay.delay_ms(10);
-                }
-            });
+            for report in reports {
+                queue_report(report, &mut queued, &mut delay, &mut watchdog);
+            }
+        }
+        if let Some(reports) = a2pi.sync_caps_lock(caps_lock.is_low().unwrap(), now_ms) {
+            for report in reports {
+                queue_report(report, &mut queued, &mut delay, &mut watchdog);
+            }
         }
-        delay.delay_ms(SCAN_LOOP_RATE_MS);
+        // an idle endpoint raises no interrupt of its own, this both sends what
+        // was queued and repeats reports at the host's idle rate.
+        pac::NVIC::pend(hal::pac::Interrupt::USBCTRL_IRQ);
     }
     //
     // -- END MAIN --
@@ -272,36 +412,72 @@ fn main() -> ! {
 unsafe fn USBCTRL_IRQ() {
     let usb_dev = USB_DEVICE.as_mut().unwrap();
     let usb_hid = USB_HID.as_mut().unwrap();
+    let nkro_hid = USB_NKRO_HID.as_mut().unwrap();
+    let config_hid = USB_CONFIG_HID.as_mut().unwrap();
+
+    let hid_requests = USB_HID_REQUESTS.as_mut().unwrap();
 
-    if usb_dev.poll(&mut [usb_hid]) {
+    // `hid_requests` goes first to answer what `HIDClass` doesn't.
+    if usb_dev.poll(&mut [hid_requests, usb_hid, nkro_hid, config_hid]) {
         usb_hid.poll();
     }
 
-    let report = critical_section::with(|cs| *KEYBOARD_REPORT.borrow_ref(cs));
-    match report {
-        KbOracleReports::Keyboard(k) => {
-            if let Err(_err) = usb_hid.push_input(&k) {
-                let _no_op = 0;
+    // a bios or uefi host asks for the boot protocol and only reads the 6kro
+    // report, everyone else gets every key over the nkro interface.
+    let boot = matches!(usb_hid.get_protocol_mode(), Ok(HidProtocolMode::Boot));
+    let now = now_us();
+
+    // one report per poll, the next goes out once the host has taken this one.
+    if let Some(report) = critical_section::with(|cs| REPORT_QUEUE.borrow_ref(cs).peek()) {
+        match push_report(usb_hid, nkro_hid, boot, &report) {
+            // still busy with the last report, retry on the next poll.
+            Err(UsbError::WouldBlock) => {}
+            Ok(_) => {
+                hid_requests.record(&report, now);
+                critical_section::with(|cs| REPORT_QUEUE.borrow_ref_mut(cs).pop());
             }
-        }
-        KbOracleReports::Consumer(c) => loop {
-            let fn_key = usb_hid.push_input(&c);
-            match fn_key {
-                Ok(_) => break,
-                Err(UsbError::WouldBlock) => {
-                    continue;
-                }
-                Err(e) => {
-                    defmt::error!("{}", e);
-                    break;
-                }
+            Err(e) => {
+                defmt::error!("dropping report: {}", e);
+                critical_section::with(|cs| REPORT_QUEUE.borrow_ref_mut(cs).pop());
             }
-        },
-    };
+        }
+    } else if let Some(report) = hid_requests.idle_report(boot, now) {
+        // nothing changed within the host's idle rate, say so again.
+        if push_report(usb_hid, nkro_hid, boot, &report).is_ok() {
+            hid_requests.record(&report, now);
+        }
+    }
 
     // macOS doesn't like it when you don't pull this, apparently.
-    // TODO: maybe even parse something here
-    usb_hid.pull_raw_output(&mut [0; 64]).ok();
+    // only led output reports arrive here, commands come over `config_hid`.
+    let mut output = [0u8; 64];
+    if let Ok(len) = usb_hid.pull_raw_output(&mut output) {
+        hid_requests.output_report(&output[..len], boot);
+    }
+    critical_section::with(|cs| *HOST_LEDS.borrow_ref_mut(cs) = hid_requests.leds());
+
+    let config_channel = CONFIG_CHANNEL.as_mut().unwrap();
+    config_channel.health.uptime_ms = (scheduler::counter() / 1_000) as u32;
+    config_channel.health.scan_overruns = scheduler::overruns();
+    config_channel.save_via_edits(&mut FlashKeyMapStore);
+    // the reboot is deferred to the interrupt after the one answering it so
+    // its response makes it out.
+    if config_channel.reboot_requested() {
+        cortex_m::peripheral::SCB::sys_reset();
+    }
+    let mut request = [0u8; CONFIG_REPORT_LEN];
+    if let Ok(len) = config_hid.pull_raw_output(&mut request) {
+        if len > 0 {
+            let response = config_channel.handle(
+                &request[..len],
+                &mut FlashKeyMapStore,
+                SETTINGS.as_mut().unwrap(),
+            );
+            if let Err(err) = config_hid.push_raw_input(&response) {
+                defmt::error!("unable to answer config request: {}", err);
+            }
+        }
+    }
 
     // Wake the host if a key is pressed and the device supports
     // remote wakeup.
@@ -310,75 +486,152 @@ unsafe fn USBCTRL_IRQ() {
     }
 }
 
+/// the report last queued of each kind, keyboard, consumer and system control.
+type QueuedReports = [Option<KbOracleReports>; 3];
+
+/// queue `report` for `USBCTRL_IRQ` unless it's the same as the last of its
+/// kind. back-pressure: wait on the interrupt to free a slot rather than drop
+/// a step of a macro. a host that stops reading (suspended, unplugged) isn't a
+/// wedge, so the watchdog is fed meanwhile.
+fn queue_report(
+    report: KbOracleReports,
+    queued: &mut QueuedReports,
+    delay: &mut cortex_m::delay::Delay,
+    watchdog: &mut hal::Watchdog,
+) {
+    let last = match report {
+        KbOracleReports::Keyboard(_) => &mut queued[0],
+        KbOracleReports::Consumer(_) => &mut queued[1],
+        KbOracleReports::System(_) => &mut queued[2],
+    };
+    // an unchanged report tells the host nothing and would only crowd the
+    // queue, repeats at the host's idle rate are `HidRequests::idle_report`'s.
+    if *last == Some(report) {
+        return;
+    }
+    *last = Some(report);
+
+    while critical_section::with(|cs| REPORT_QUEUE.borrow_ref_mut(cs).push(report)).is_err() {
+        pac::NVIC::pend(hal::pac::Interrupt::USBCTRL_IRQ);
+        watchdog.feed();
+        delay.delay_ms(1);
+    }
+}
+
+/// send `report` on the interface the host's protocol reads it from.
+fn push_report(
+    usb_hid: &mut HIDClass<'static, UsbBus>,
+    nkro_hid: &mut HIDClass<'static, UsbBus>,
+    boot: bool,
+    report: &KbOracleReports,
+) -> Result<usize, UsbError> {
+    match report {
+        KbOracleReports::Keyboard(k) if boot => usb_hid.push_raw_input(&boot_bytes(&k.to_boot())),
+        KbOracleReports::Keyboard(k) => nkro_hid.push_raw_input(&k.to_bytes()),
+        // there's no consumer or system control report in the boot protocol.
+        _ if boot => Ok(0),
+        _ => {
+            let (bytes, len) = report.to_bytes();
+            usb_hid.push_raw_input(&bytes[..len])
+        }
+    }
+}
+
+/// the free running µs counter of the rp2040 timer, wrapping every ~71 minutes.
+fn now_us() -> u32 {
+    unsafe { (*pac::TIMER::ptr()).timerawl.read().bits() }
+}
+
+/// the watchdog's reason is zero after a chip level reset, which
+/// `chip_reset` tells apart.
+fn reset_reason(watchdog: &pac::WATCHDOG, chip: &pac::VREG_AND_CHIP_RESET) -> ResetReason {
+    let reason = watchdog.reason.read();
+    let chip_reset = chip.chip_reset.read();
+    if reason.timer().bit_is_set() {
+        ResetReason::Watchdog
+    } else if reason.force().bit_is_set() {
+        // the rom bootloader reboots through a forced watchdog reset.
+        ResetReason::Bootloader
+    } else if chip_reset.had_psm_restart().bit_is_set() {
+        ResetReason::Debugger
+    } else if chip_reset.had_run().bit_is_set() {
+        ResetReason::RunPin
+    } else {
+        ResetReason::PowerOn
+    }
+}
+
+/// the debounce of the keys or the modifier lines, from their settings.
+fn debounce_config(
+    settings: &impl SettingsStore,
+    mode: Setting,
+    press_ticks: Setting,
+    release_ticks: Setting,
+) -> DebounceConfig {
+    DebounceConfig {
+        // stored values were checked by `Setting::accepts`.
+        mode: DebounceMode::get(settings.get(mode)).unwrap_or(DebounceMode::EagerPress),
+        press_ticks: settings.get(press_ticks) as u8,
+        release_ticks: settings.get(release_ticks) as u8,
+    }
+}
+
+fn firmware_version() -> [u8; 3] {
+    [
+        env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap_or(0),
+        env!("CARGO_PKG_VERSION_MINOR").parse().unwrap_or(0),
+        env!("CARGO_PKG_VERSION_PATCH").parse().unwrap_or(0),
+    ]
+}
+
 fn report_is_empty(report: &KeyboardReport) -> bool {
     report.modifier != 0 || report.keycodes.iter().any(|key| *key != 0x0u8)
 }
 
 static mut CORE1_STACK: Stack<4096> = Stack::new();
 
-fn core1_task(sys_freq: u32) -> ! {
-    loop {}
-    /*
-    let mut pac = unsafe { pac::Peripherals::steal() };
-    let core = unsafe { pac::CorePeripherals::steal() };
-
+/// scan on every tick of `scheduler` and send core 0 what changed, then a
+/// `ScanEvent::ScanEnd`. core 0 can fall behind during a long macro, so what
+/// the fifo has no room for waits here, and ends of scans it has yet to see
+/// are sent as one.
+fn core1_task(
+    mut matrix: MatrixScanner,
+    mut debounce: Debounce<NUM_MODS, NUM_ROWS, NUM_COLS>,
+    alarm: hal::timer::Alarm0,
+    scan_rate_ms: u32,
+) -> ! {
+    let pac = unsafe { pac::Peripherals::steal() };
     let mut sio = hal::Sio::new(pac.SIO);
-    /*
-    let pins = hal::gpio::Pins::new(
-        pac.IO_BANK0,
-        pac.PADS_BANK0,
-        sio.gpio_bank0,
-        &mut pac.RESETS,
-    );
-    */
+    // the alarm's interrupt goes to the core that unmasks it.
+    scheduler::start(alarm, scan_rate_ms);
 
-    let mut delay = cortex_m::delay::Delay::new(core.SYST, sys_freq);
-
-    // let buffer: [u8; 3] = [0x0u8; 3];
-    let mut buffer = Vec::new();
+    let mut previous = KeyScan::released();
+    let mut pending = VecDeque::new();
     loop {
-        let input = sio.fifo.read();
-        if let Some(word) = input {
-            let byte = word;
-            if byte == 512 {
-                buffer.clear();
-            } else {
-                buffer.push(byte as u8);
-            }
+        scheduler::wait();
+        // sit out any flash write of core 0's here, see `storage`.
+        storage::park_core1_if_asked();
+        let (raw_modifiers, raw_matrix) = matrix.scan();
+        let mut key_scan = KeyScan::from_raw(&raw_modifiers, &raw_matrix, &mut debounce);
+        key_scan.suppress_ghosts(&previous);
+        pending.extend(key_scan.changes(&previous).map(ScanEvent::to_word));
+        previous = key_scan;
+
+        let scan_end = ScanEvent::ScanEnd {
+            ghosted: key_scan.ghosted(),
         };
-        if buffer.len() == 3 {
-            defmt::info!(
-                "[KEY_PRESS]: {=[u8]:#x} ::::: {:?}",
-                buffer.as_slice(),
-                buffer.as_slice()
-            );
-            // process buffer
-            let reports: Option<Vec<KeyboardReport>> = critical_section::with(|cs| unsafe {
-                let mut a2pi = unsafe { A2PI_DRIVER_KB.borrow_ref_mut(cs) };
-                a2pi.as_mut().unwrap().process_key_event({
-                    let mut buf: [u8; 3] = [0x0u8; 3];
-                    buffer.iter().enumerate().for_each(|(idx, &b)| buf[idx] = b);
-                    buf
-                })
-            });
-            critical_section::with(|cs| unsafe {
-                if let Some(keyboard_reports) = reports {
-                    let num_reports = keyboard_reports.len();
-                    for (idx, &report) in keyboard_reports.iter().enumerate() {
-                        defmt::info!("writing report... {}", report.keycodes);
-                        KEYBOARD_REPORT.replace(cs, report);
-                        // delay.delay_ms(10);
-                        // unless a case exists where there are multiple reports to be
-                        // emitted, do not incur a delay.
-                        if idx != num_reports {
-                            defmt::info!("delaying next report...");
-                        }
-                    }
-                }
-            });
-            buffer.clear();
+        let unsent = pending.back().and_then(|word| ScanEvent::from_word(*word));
+        if let Some(ScanEvent::ScanEnd { .. }) = unsent {
+            pending.pop_back();
+        }
+        pending.push_back(scan_end.to_word());
+
+        while let Some(word) = pending.front() {
+            if !sio.fifo.is_write_ready() {
+                break;
+            }
+            sio.fifo.write(*word);
+            pending.pop_front();
         }
     }
-    // sio.fifo.write_blocking(CORE1_TASK_COMPLETE);
-    */
 }
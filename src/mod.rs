@@ -1,11 +0,0 @@
-#![no_std]
-
-pub mod drivers;
-pub mod errors;
-pub mod shims;
-pub mod state;
-
-#[cfg(feature = "no-std")]
-extern crate alloc;
-#[cfg(feature = "no-std")]
-pub mod utils;
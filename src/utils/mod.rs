@@ -1,2 +0,0 @@
-#[cfg(feature = "no-std")]
-pub mod hex;